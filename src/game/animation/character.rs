@@ -1,5 +1,5 @@
 use crate::game::animation::{Animation, Direction};
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::backend::Renderer;
 
 enum Sprite {
   StandLeft,
@@ -16,7 +16,8 @@ enum Sprite {
   WalkDown2,
   Dead,
   Attack,
-  Victory
+  Victory,
+  Guard
 }
 
 pub struct CharacterAnimation {
@@ -44,8 +45,10 @@ impl CharacterAnimation {
       Animation::StartTurn      => self.remaining_frames = 12,
       Animation::EndTurn        => self.remaining_frames = 10,
       Animation::Attack         => self.remaining_frames = 40,
-      Animation::Hurt(_, _)     => self.remaining_frames = 40,
-      Animation::HurtSelf(_, _) => self.remaining_frames = 24,
+      Animation::Cast           => self.remaining_frames = 40,
+      Animation::Defend         => self.remaining_frames = 10,
+      Animation::Hurt(_, _, _)     => self.remaining_frames = 40,
+      Animation::HurtSelf(_, _, _) => self.remaining_frames = 24,
       Animation::Dead           => self.remaining_frames = 20,
       Animation::Victory        => self.remaining_frames = 180,
       Animation::Flee           => self.remaining_frames = 80,
@@ -78,9 +81,26 @@ impl CharacterAnimation {
           self.sprite = Sprite::StandRight;
           return true;
         }
+        false
+      },
+      Animation::Cast => {
+        if self.remaining_frames == 32 {
+          self.sprite = Sprite::Victory;
+        } else if self.remaining_frames == 0 {
+          self.sprite = Sprite::StandRight;
+          return true;
+        }
         return false;
       },
-      Animation::Hurt(_, _) | Animation::HurtSelf(_, _) => {
+      Animation::Defend => {
+        self.advance_walk_animation(Direction::Left);
+        if self.remaining_frames == 0 {
+          self.sprite = Sprite::Guard;
+          return true;
+        }
+        false
+      },
+      Animation::Hurt(_, _, _) | Animation::HurtSelf(_, _, _) => {
         if self.remaining_frames <= 24 {
           if self.remaining_frames % 8 == 0 {
             self.opacity = 1.;
@@ -155,6 +175,10 @@ impl CharacterAnimation {
     }
   }
 
+  pub fn get_portrait_key(&self) -> String {
+    format!("{}/avatar", self.sprite_folder)
+  }
+
   fn get_sprite_key(&self) -> String {
     match self.sprite {
       Sprite::StandLeft  => format!("{}/standing_left",      self.sprite_folder),
@@ -171,11 +195,12 @@ impl CharacterAnimation {
       Sprite::WalkDown2  => format!("{}/walk_frame_2_down",  self.sprite_folder),
       Sprite::Dead       => format!("{}/dead",               self.sprite_folder),
       Sprite::Attack     => format!("{}/attack",             self.sprite_folder),
-      Sprite::Victory    => format!("{}/victory",            self.sprite_folder)
+      Sprite::Victory    => format!("{}/victory",            self.sprite_folder),
+      Sprite::Guard      => format!("{}/guard",              self.sprite_folder)
     }
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram, x: f32, y: f32) {
+  pub fn draw(&self, program: &mut dyn Renderer, x: f32, y: f32) {
     program.draw(self.get_sprite_key(), x, y, 64., 64., self.opacity);
   }
 }
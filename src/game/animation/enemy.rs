@@ -1,5 +1,5 @@
 use crate::game::animation::Animation;
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::backend::Renderer;
 
 pub struct EnemyAnimation {
   sprite_key: String,
@@ -26,8 +26,8 @@ impl EnemyAnimation {
       Animation::StartTurn      => self.remaining_frames = 12,
       Animation::EndTurn        => self.remaining_frames = 10,
       Animation::Attack         => self.remaining_frames = 40,
-      Animation::Hurt(_, _)     => self.remaining_frames = 40,
-      Animation::HurtSelf(_, _) => self.remaining_frames = 24,
+      Animation::Hurt(_, _, _)     => self.remaining_frames = 40,
+      Animation::HurtSelf(_, _, _) => self.remaining_frames = 24,
       Animation::Dead           => self.remaining_frames = 20,
       Animation::Flee           => self.remaining_frames = 80,
       _ => ()
@@ -51,7 +51,7 @@ impl EnemyAnimation {
         }
         return self.remaining_frames == 0;
       },
-      Animation::Hurt(_, _) | Animation::HurtSelf(_, _) => {
+      Animation::Hurt(_, _, _) | Animation::HurtSelf(_, _, _) => {
         if self.remaining_frames <= 24 {
           if self.remaining_frames % 8 == 0 {
             self.opacity = 1.;
@@ -82,7 +82,11 @@ impl EnemyAnimation {
     self.remaining_frames
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram, x: f32, y: f32) {
-    program.draw(self.sprite_key.to_owned(), x + self.x_offset, y, 64., 64., self.opacity);
+  pub fn get_portrait_key(&self) -> String {
+    self.sprite_key.to_owned()
+  }
+
+  pub fn draw(&self, program: &mut dyn Renderer, x: f32, y: f32, width: f32, height: f32) {
+    program.draw(self.sprite_key.to_owned(), x + self.x_offset, y, width, height, self.opacity);
   }
 }
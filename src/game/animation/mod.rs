@@ -1,9 +1,11 @@
 pub mod character;
 pub mod enemy;
 
-use crate::game::battle::state::BattleState;
+use crate::game::battle::TargetAction;
+use crate::game::battle::element::Element;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum Direction {
   Up, Down, Left, Right
 }
@@ -13,8 +15,10 @@ pub enum Animation {
   StartTurn,
   EndTurn,
   Attack,
-  Hurt(for<'a> fn(&'a mut BattleState, f64), f64),
-  HurtSelf(for<'a> fn(&'a mut BattleState, f64), f64),
+  Cast,
+  Defend,
+  Hurt(TargetAction, f64, Option<Element>),
+  HurtSelf(TargetAction, f64, Option<Element>),
   Dead,
   Victory,
   Flee,
@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+use crate::game::backend::{AudioPlayer, Input, Renderer, Rng};
+
+// Backends for running the game core without a browser, e.g. natively from a test or CLI.
+
+pub struct ScriptedInput {
+  frames: VecDeque<Vec<String>>,
+  down: Vec<String>,
+  held: Vec<String>
+}
+
+impl ScriptedInput {
+  pub fn new(frames: Vec<Vec<&str>>) -> Self {
+    Self {
+      frames: frames.iter().map(|keys: &Vec<&str>| keys.iter().map(|key: &&str| key.to_string()).collect()).collect(),
+      down: Vec::new(),
+      held: Vec::new()
+    }
+  }
+
+  pub fn push_frame(&mut self, keys: Vec<&str>) {
+    self.frames.push_back(keys.iter().map(|key: &&str| key.to_string()).collect());
+  }
+
+  // Moves on to the next scripted frame, releasing every key that is not down in it.
+  pub fn advance_frame(&mut self) {
+    self.down = self.frames.pop_front().unwrap_or_default();
+    let down = &self.down;
+    self.held.retain(|key: &String| down.contains(key));
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.frames.is_empty()
+  }
+}
+
+impl Input for ScriptedInput {
+  fn is_pressed(&mut self, key: &str) -> bool {
+    if self.is_down(key) && !self.held.iter().any(|held_key: &String| held_key == key) {
+      self.held.push(key.to_string());
+      return true;
+    }
+    false
+  }
+
+  fn is_down(&self, key: &str) -> bool {
+    self.down.iter().any(|down_key: &String| down_key == key)
+  }
+}
+
+pub struct SequenceRng {
+  values: Vec<f64>,
+  index: usize
+}
+
+impl SequenceRng {
+  pub fn new(values: Vec<f64>) -> Self {
+    Self {
+      values,
+      index: 0
+    }
+  }
+}
+
+impl Rng for SequenceRng {
+  fn random(&mut self) -> f64 {
+    if self.values.is_empty() {
+      return 0.;
+    }
+    let value = self.values[self.index % self.values.len()];
+    self.index += 1;
+    value
+  }
+}
+
+#[derive(Default)]
+pub struct RecordingAudio {
+  soundtrack: Option<String>,
+  sfxs: Vec<String>
+}
+
+impl RecordingAudio {
+  pub fn new() -> Self {
+    Self {
+      soundtrack: None,
+      sfxs: Vec::new()
+    }
+  }
+
+  pub fn get_soundtrack(&self) -> Option<&String> {
+    self.soundtrack.as_ref()
+  }
+
+  pub fn get_played_sfxs(&self) -> &Vec<String> {
+    &self.sfxs
+  }
+}
+
+impl AudioPlayer for RecordingAudio {
+  fn update(&mut self, file_name: &str) {
+    if file_name.is_empty() {
+      self.soundtrack = None;
+    } else {
+      self.soundtrack = Some(file_name.to_owned());
+    }
+  }
+
+  fn play_sfx(&mut self, file_name: &str) {
+    self.sfxs.push(file_name.to_string());
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawCall {
+  pub key: String,
+  pub x: f32,
+  pub y: f32,
+  pub width: f32,
+  pub height: f32,
+  pub opacity: f32
+}
+
+#[derive(Default)]
+pub struct RecordingRenderer {
+  draw_calls: Vec<DrawCall>
+}
+
+impl RecordingRenderer {
+  pub fn new() -> Self {
+    Self {
+      draw_calls: Vec::new()
+    }
+  }
+
+  pub fn get_draw_calls(&self) -> &Vec<DrawCall> {
+    &self.draw_calls
+  }
+
+  pub fn take_frame(&mut self) -> Vec<DrawCall> {
+    self.draw_calls.drain(..).collect()
+  }
+}
+
+impl Renderer for RecordingRenderer {
+  fn draw(&mut self, key: String, x: f32, y: f32, width: f32, height: f32, opacity: f32) {
+    self.draw_calls.push(DrawCall {key, x, y, width, height, opacity});
+  }
+}
@@ -0,0 +1,19 @@
+pub mod headless;
+
+pub trait Input {
+  fn is_pressed(&mut self, key: &str) -> bool;
+  fn is_down(&self, key: &str) -> bool;
+}
+
+pub trait Rng {
+  fn random(&mut self) -> f64;
+}
+
+pub trait AudioPlayer {
+  fn update(&mut self, file_name: &str);
+  fn play_sfx(&mut self, file_name: &str);
+}
+
+pub trait Renderer {
+  fn draw(&mut self, key: String, x: f32, y: f32, width: f32, height: f32, opacity: f32);
+}
@@ -0,0 +1,157 @@
+use serde::Deserialize;
+use crate::game::animation::Animation;
+use crate::game::backend::Rng;
+use crate::game::battle::{ActionTuple, BattleActionTargetStart, DamageType, TargetAction, TargetScope};
+use crate::game::battle::element::Element;
+use crate::game::battle::state::BattleState;
+use crate::game::battle::status::{StatusChange, StatusEffect};
+use crate::game::data::battle_menus::TargetingMenu;
+use crate::game::menu::click_event::OnClickEvent;
+use crate::game::menu::item::MenuItem;
+use crate::game::menu::notification::Notification;
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbilityAnimation {
+  #[default]
+  Attack,
+  Cast
+}
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageSpread {
+  #[default]
+  Full,
+  Split
+}
+
+#[derive(Clone)]
+pub struct Ability {
+  name: String,
+  mp_cost: u16,
+  power: f64,
+  targeting: TargetingMenu,
+  scope: TargetScope,
+  action: Option<ActionTuple>,
+  status_change: Option<StatusChange>,
+  element: Option<Element>,
+  target_start: BattleActionTargetStart,
+  animation: AbilityAnimation,
+  hits: u32,
+  spread: DamageSpread
+}
+
+pub struct AbilityParams {
+  pub mp_cost: u16,
+  pub power: f64,
+  pub targeting: TargetingMenu,
+  pub scope: TargetScope,
+  pub action: Option<ActionTuple>,
+  pub status_change: Option<StatusChange>,
+  pub element: Option<Element>,
+  pub target_start: BattleActionTargetStart,
+  pub animation: AbilityAnimation,
+  pub hits: u32,
+  pub spread: DamageSpread
+}
+
+impl Ability {
+  pub fn new(name: String, params: AbilityParams) -> Self {
+    Self {
+      name,
+      mp_cost: params.mp_cost,
+      power: params.power,
+      targeting: params.targeting,
+      scope: params.scope,
+      action: params.action,
+      status_change: params.status_change,
+      element: params.element,
+      target_start: params.target_start,
+      animation: params.animation,
+      hits: params.hits,
+      spread: params.spread
+    }
+  }
+
+  pub fn get_name(&self) -> String {
+    self.name.to_owned()
+  }
+
+  pub fn get_mp_cost(&self) -> u16 {
+    self.mp_cost
+  }
+
+  pub fn get_target_start(&self) -> BattleActionTargetStart {
+    self.target_start
+  }
+
+  pub fn get_scope(&self) -> TargetScope {
+    self.scope
+  }
+
+  pub fn get_hits(&self) -> u32 {
+    self.hits
+  }
+
+  // split damage is shared out between every hit instead of each one landing in full
+  pub fn get_damage_per_hit(&self, incoming_damage: f64, hit_count: usize) -> f64 {
+    match self.spread {
+      DamageSpread::Full => incoming_damage,
+      DamageSpread::Split => (incoming_damage / hit_count.max(1) as f64).round()
+    }
+  }
+
+  pub fn get_target_action(&self) -> Option<TargetAction> {
+    self.action.map(|action: ActionTuple| action.1)
+  }
+
+  pub fn get_element(&self) -> Option<Element> {
+    self.element
+  }
+
+  pub fn get_damage_type(&self) -> Option<DamageType> {
+    self.action.map(|action: ActionTuple| action.2)
+  }
+
+  // spells are the abilities that silence shuts off
+  pub fn is_spell(&self) -> bool {
+    match self.get_damage_type() {
+      Some(DamageType::Physical) => false,
+      Some(_) => true,
+      None => self.mp_cost > 0
+    }
+  }
+
+  pub fn get_status_change(&self) -> Option<&StatusChange> {
+    self.status_change.as_ref()
+  }
+
+  pub fn get_animation(&self) -> Animation {
+    match self.animation {
+      AbilityAnimation::Attack => Animation::Attack,
+      AbilityAnimation::Cast   => Animation::Cast
+    }
+  }
+
+  pub fn can_use(&self, actor_state: &BattleState) -> bool {
+    let silenced = self.is_spell() && actor_state.has_status(StatusEffect::Silence);
+    actor_state.get_mp() >= self.mp_cost && !silenced
+  }
+
+  pub fn perform(&self, actor_state: &mut BattleState, notification: &mut Notification, rng: &mut dyn Rng) -> f64 {
+    actor_state.reduce_mp(self.mp_cost);
+    notification.set_notification(self.get_name());
+    match self.action {
+      // whole points only, so what lands and what pops up never carry fractions
+      Some(action) => (action.0(actor_state, notification, rng) * self.power).round(),
+      None => 0.
+    }
+  }
+
+  pub fn as_menuitem(&self, actor_state: &BattleState, x: f32, y: f32) -> MenuItem {
+    let mut menu_item = MenuItem::new(self.get_name(), x, y, OnClickEvent::ToTargetSelection(self.targeting, self.clone()));
+    menu_item.set_enabled(self.can_use(actor_state));
+    menu_item
+  }
+}
@@ -0,0 +1,254 @@
+use serde::Deserialize;
+use crate::game::backend::Rng;
+use crate::game::battle::{BattleRow, TargetScope};
+use crate::game::battle::ability::Ability;
+use crate::game::battle::character::Character;
+use crate::game::battle::enemy::Enemy;
+use crate::game::battle::state::BattleState;
+use crate::game::menu::notification::Notification;
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum AiCondition {
+  #[default]
+  Always,
+  OwnHpBelow { percent: f64 },
+  AllyHpBelow { percent: f64 },
+  EveryNthTurn { turns: u32 }
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AiTarget {
+  RandomMember, LowestHpMember, Myself, LowestHpAlly
+}
+
+#[derive(Clone)]
+pub struct AiRule {
+  ability: Ability,
+  target: AiTarget,
+  condition: AiCondition,
+  priority: u32,
+  weight: u32
+}
+
+impl AiRule {
+  pub fn new(ability: Ability, target: AiTarget, condition: AiCondition, priority: u32, weight: u32) -> Self {
+    Self {
+      ability,
+      target,
+      condition,
+      priority,
+      weight
+    }
+  }
+}
+
+fn random_index(rng: &mut dyn Rng, length: usize) -> usize {
+  (rng.random() * length as f64).floor() as usize
+}
+
+fn hp_percent(state: &BattleState) -> f64 {
+  state.get_hp() as f64 * 100. / state.get_max_hp() as f64
+}
+
+fn condition_holds(condition: AiCondition, actor: &Enemy, enemies: &[Vec<Enemy>]) -> bool {
+  match condition {
+    AiCondition::Always => true,
+    AiCondition::OwnHpBelow { percent } => hp_percent(actor.get_battle_state()) < percent,
+    AiCondition::AllyHpBelow { percent } => enemies.iter().flatten()
+      .map(|enemy: &Enemy| enemy.get_battle_state())
+      .any(|state: &BattleState| state.get_hp() > 0 && hp_percent(state) < percent),
+    AiCondition::EveryNthTurn { turns } => actor.get_turns_taken().is_multiple_of(turns)
+  }
+}
+
+// every id returned is alive, so whatever gets picked is a valid target
+fn get_target_candidates(target: AiTarget, actor: &Enemy, party: &[Character], enemies: &[Vec<Enemy>]) -> Vec<usize> {
+  let alive_members = party.iter().filter(|character: &&Character| character.get_battle_state().get_hp() > 0);
+  match target {
+    AiTarget::RandomMember => {
+      // go after members that are not guarding whenever there are any
+      let alive_members: Vec<&Character> = alive_members.collect();
+      let open_ids: Vec<usize> = alive_members.iter()
+        .filter(|character: &&&Character| !character.get_battle_state().is_guarding())
+        .map(|character: &&Character| character.get_id())
+        .collect();
+      if !open_ids.is_empty() {
+        open_ids
+      } else {
+        alive_members.iter().map(|character: &&Character| character.get_id()).collect()
+      }
+    },
+    AiTarget::LowestHpMember => alive_members
+      .min_by_key(|character: &&Character| character.get_battle_state().get_hp())
+      .map(|character: &Character| vec![character.get_id()])
+      .unwrap_or_default(),
+    AiTarget::Myself => vec![actor.get_id()],
+    AiTarget::LowestHpAlly => enemies.iter().flatten()
+      .filter(|enemy: &&Enemy| enemy.get_battle_state().get_hp() > 0 && enemy.get_battle_state().can_restore_hp())
+      .min_by(|a: &&Enemy, b: &&Enemy| hp_percent(a.get_battle_state()).total_cmp(&hp_percent(b.get_battle_state())))
+      .map(|enemy: &Enemy| vec![enemy.get_id()])
+      .unwrap_or_default()
+  }
+}
+
+fn choose_rule(rng: &mut dyn Rng, candidates: Vec<(&AiRule, Vec<usize>)>) -> Option<(Ability, Vec<usize>)> {
+  let top_priority = candidates.iter().map(|(rule, _): &(&AiRule, Vec<usize>)| rule.priority).max()?;
+  let top_candidates: Vec<(&AiRule, Vec<usize>)> = candidates.into_iter()
+    .filter(|(rule, _): &(&AiRule, Vec<usize>)| rule.priority == top_priority)
+    .collect();
+  let total_weight: u32 = top_candidates.iter().map(|(rule, _): &(&AiRule, Vec<usize>)| rule.weight).sum();
+  let mut roll = rng.random() * total_weight as f64;
+  for (rule, target_ids) in top_candidates.iter() {
+    roll -= rule.weight as f64;
+    if roll < 0. {
+      return Some((rule.ability.clone(), target_ids.to_owned()));
+    }
+  }
+  top_candidates.last().map(|(rule, target_ids): &(&AiRule, Vec<usize>)| (rule.ability.clone(), target_ids.to_owned()))
+}
+
+pub fn perform_enemy_turn(rng: &mut dyn Rng, party: &mut [Character], enemies: &mut [Vec<Enemy>], notification: &mut Notification) {
+  let (ability, target_ids) = {
+    let acting_enemy = match enemies.iter().flatten().find(|enemy: &&Enemy| enemy.get_battle_state().is_turn_active()) {
+      Some(enemy) => enemy,
+      None => return
+    };
+    let candidates = acting_enemy.get_ai().iter()
+      .filter(|rule: &&AiRule| condition_holds(rule.condition, acting_enemy, enemies) && rule.ability.can_use(acting_enemy.get_battle_state()))
+      .map(|rule: &AiRule| (rule, get_target_candidates(rule.target, acting_enemy, party, enemies)))
+      .filter(|(_, target_ids): &(&AiRule, Vec<usize>)| !target_ids.is_empty())
+      .collect();
+    match choose_rule(rng, candidates) {
+      Some(choice) => choice,
+      None => return
+    }
+  };
+  let target_id = target_ids[random_index(rng, target_ids.len())];
+  let target_ids = spread_targets(rng, &ability, target_id, party, enemies);
+
+  let acting_enemy = enemies.iter_mut().flatten().find(|enemy: &&mut Enemy| enemy.get_battle_state().is_turn_active()).unwrap();
+  let incoming_damage = ability.perform(acting_enemy.get_battle_state_mut(), notification, rng);
+  let damage_per_hit = ability.get_damage_per_hit(incoming_damage, target_ids.len());
+  // every hit on the same battler lands at once, like the party's own group abilities
+  for character in party.iter_mut() {
+    let hits = target_ids.iter().filter(|id: &&usize| **id == character.get_id()).count();
+    if hits == 0 {
+      continue;
+    }
+    for _ in 0..hits {
+      if let Some(status_change) = ability.get_status_change() {
+        character.get_battle_state_mut().apply_status_change(status_change, rng);
+      }
+    }
+    if let Some(target_action) = ability.get_target_action() {
+      character.receive_battle_action(target_action, damage_per_hit * hits as f64, ability.get_element());
+    }
+  }
+  for enemy in enemies.iter_mut().flatten() {
+    let hits = target_ids.iter().filter(|id: &&usize| **id == enemy.get_id()).count();
+    if hits == 0 {
+      continue;
+    }
+    for _ in 0..hits {
+      if let Some(status_change) = ability.get_status_change() {
+        enemy.get_battle_state_mut().apply_status_change(status_change, rng);
+      }
+    }
+    if let Some(target_action) = ability.get_target_action() {
+      enemy.receive_battle_action(target_action, damage_per_hit * hits as f64, ability.get_element());
+    }
+  }
+}
+
+// group abilities reach the rest of the target's side, split into party rows or enemy rows
+fn spread_targets(rng: &mut dyn Rng, ability: &Ability, target_id: usize, party: &[Character], enemies: &[Vec<Enemy>]) -> Vec<usize> {
+  let rows: Vec<Vec<usize>> = if party.iter().any(|character: &Character| character.get_id() == target_id) {
+    [BattleRow::Front, BattleRow::Back].iter().map(|row: &BattleRow| party.iter()
+      .filter(|character: &&Character| character.get_battle_state().get_hp() > 0 && character.get_battle_state().get_row() == *row)
+      .map(|character: &Character| character.get_id())
+      .collect()
+    ).collect()
+  } else {
+    enemies.iter().map(|enemy_row: &Vec<Enemy>| enemy_row.iter()
+      .filter(|enemy: &&Enemy| enemy.get_battle_state().get_hp() > 0)
+      .map(|enemy: &Enemy| enemy.get_id())
+      .collect()
+    ).collect()
+  };
+  match ability.get_scope() {
+    TargetScope::Single => vec![target_id],
+    TargetScope::Row => rows.into_iter().find(|row: &Vec<usize>| row.contains(&target_id)).unwrap_or_default(),
+    TargetScope::All => rows.concat(),
+    TargetScope::Random => {
+      let side = rows.concat();
+      (0..ability.get_hits()).map(|_| side[random_index(rng, side.len())]).collect()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::backend::headless::SequenceRng;
+  use crate::game::data::content::Content;
+
+  // reuses the default Circle's abilities, which all have different names, with the given priorities and weights
+  fn rules(priorities_and_weights: &[(u32, u32)]) -> Vec<AiRule> {
+    let circle = Content::default_content().create_enemy("Circle", 5).unwrap();
+    circle.get_ai().iter().zip(priorities_and_weights.iter())
+      .map(|(rule, (priority, weight)): (&AiRule, &(u32, u32))| AiRule::new(rule.ability.clone(), rule.target, rule.condition, *priority, *weight))
+      .collect()
+  }
+
+  fn chosen_name(roll: f64, rules: &[AiRule]) -> Option<String> {
+    let candidates = rules.iter().enumerate().map(|(i, rule): (usize, &AiRule)| (rule, vec![i + 1])).collect();
+    choose_rule(&mut SequenceRng::new(vec![roll]), candidates).map(|(ability, _): (Ability, Vec<usize>)| ability.get_name())
+  }
+
+  #[test]
+  fn highest_priority_rule_wins_over_weight() {
+    let rules = rules(&[(0, 100), (1, 1), (0, 100)]);
+    let expected = Some(rules[1].ability.get_name());
+    assert_eq!(chosen_name(0., &rules), expected);
+    assert_eq!(chosen_name(0.99, &rules), expected);
+  }
+
+  #[test]
+  fn rules_with_the_same_priority_split_by_weight() {
+    let rules = rules(&[(2, 1), (2, 3), (0, 10)]);
+    assert_eq!(chosen_name(0.2, &rules), Some(rules[0].ability.get_name()));
+    assert_eq!(chosen_name(0.3, &rules), Some(rules[1].ability.get_name()));
+    assert_eq!(chosen_name(0.99, &rules), Some(rules[1].ability.get_name()));
+  }
+
+  #[test]
+  fn no_usable_rule_means_no_action() {
+    assert_eq!(chosen_name(0.5, &[]), None);
+  }
+
+  fn skill(character: &Character, name: &str) -> Ability {
+    character.get_skills().iter().find(|skill: &&Ability| skill.get_name() == name).unwrap().clone()
+  }
+
+  #[test]
+  fn group_abilities_spread_over_the_target_side() {
+    let content = Content::default_content();
+    let mut party = content.create_starting_party();
+    party[1].get_battle_state_mut().set_row(BattleRow::Back);
+    party[3].get_battle_state_mut().reduce_hp(u16::MAX);
+    let enemies = content.create_formation("test_room_formation_2").unwrap();
+    let mut rng = SequenceRng::new(vec![0.]);
+
+    let cleave = skill(&party[0], "Cleave");
+    assert_eq!(spread_targets(&mut rng, &cleave, 1, &party, &enemies), vec![1, 3]);
+    assert_eq!(spread_targets(&mut rng, &cleave, 7, &party, &enemies), vec![7]);
+    let curaga = skill(&party[1], "Curaga");
+    assert_eq!(spread_targets(&mut rng, &curaga, 5, &party, &enemies), vec![5, 6, 7]);
+    assert_eq!(spread_targets(&mut rng, &curaga, 2, &party, &enemies), vec![1, 3, 2]);
+    let flurry = skill(&party[0], "Flurry");
+    assert_eq!(spread_targets(&mut rng, &flurry, 2, &party, &enemies), vec![1; 4]);
+    assert_eq!(spread_targets(&mut rng, &party[0].get_skills()[0], 2, &party, &enemies), vec![2]);
+  }
+}
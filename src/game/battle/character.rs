@@ -1,23 +1,18 @@
-use crate::game::battle::enemy::Enemy;
 use crate::game::animation::{Animation, Direction};
 use crate::game::animation::character::CharacterAnimation;
-use crate::game::battle::ActionTuple;
-use crate::game::battle::BattleActionTargetStart;
+use crate::game::backend::{AudioPlayer, Renderer};
+use crate::game::battle::{BattleRow, TargetAction};
+use crate::game::battle::ability::Ability;
+use crate::game::battle::element::Element;
 use crate::game::battle::print_damage::PrintDamage;
-use crate::game::battle::state::BattleState;
+use crate::game::battle::state::{BaseStats, BattleState, GrowthRates};
 use crate::game::data::battle_menus;
-use crate::game::menu::click_event::OnClickEvent;
+use crate::game::inventory::equipment::{Equipment, EquipmentSlot, StatBonuses};
 use crate::game::menu::item::MenuItem;
 use crate::game::menu::MenuScreen;
-use crate::webgl::audio::Audio;
-use crate::webgl::shader_program::ShaderProgram;
 
-type AbilityTuple = (
-  String,
-  for<'r, 's> fn(&'r Vec<Character>, &'s mut Vec<Vec<Enemy>>, ActionTuple, BattleActionTargetStart) -> MenuScreen,
-  ActionTuple,
-  BattleActionTargetStart
-);
+const FRONT_ROW_X: f32 = 100.;
+const BACK_ROW_X: f32 = 52.;
 
 pub struct Character {
   animation: CharacterAnimation,
@@ -26,28 +21,30 @@ pub struct Character {
   x: f32,
   y: f32,
   state: BattleState,
-  attack_ability: AbilityTuple,
-  primary_ability: AbilityTuple,
-  secondary_ability: AbilityTuple
+  equipment: [Option<Equipment>; 3],
+  attack_ability: Ability,
+  primary_ability: Ability,
+  secondary_ability: Ability,
+  skills: Vec<Ability>,
+  locked: bool
+}
+
+pub struct CharacterAbilities {
+  pub attack: Ability,
+  pub primary: Ability,
+  pub secondary: Ability,
+  pub skills: Vec<Ability>
 }
 
 impl Character {
   pub fn new(
     name: String,
     sprite_folder: String,
-    id: usize, 
+    id: usize,
     level: u32,
-    hp:  u16, hp_growth_rate:  f32,
-    mp:  u16, mp_growth_rate:  f32,
-    att: f64, att_growth_rate: f32,
-    def: f64, def_growth_rate: f32,
-    mag: f64, mag_growth_rate: f32,
-    int: f64, int_growth_rate: f32,
-    res: f64, res_growth_rate: f32,
-    agi: f64, agi_growth_rate: f32,
-    attack_ability: AbilityTuple,
-    primary_ability: AbilityTuple,
-    secondary_ability: AbilityTuple
+    stats: BaseStats,
+    growth_rates: GrowthRates,
+    abilities: CharacterAbilities
   ) -> Self {
     Self {
       animation: CharacterAnimation::new(sprite_folder),
@@ -55,53 +52,54 @@ impl Character {
       id,
       x: 0.,
       y: 0.,
-      state: BattleState::new(
-        level,
-        hp,  hp_growth_rate,
-        mp,  mp_growth_rate,
-        att, att_growth_rate,
-        def, def_growth_rate,
-        mag, mag_growth_rate,
-        int, int_growth_rate,
-        res, res_growth_rate,
-        agi, agi_growth_rate
-      ),
-      attack_ability,
-      primary_ability,
-      secondary_ability
+      state: BattleState::new(level, stats, growth_rates),
+      equipment: [None, None, None],
+      attack_ability: abilities.attack,
+      primary_ability: abilities.primary,
+      secondary_ability: abilities.secondary,
+      skills: abilities.skills,
+      locked: false
     }
   }
 
-  pub fn update(&mut self, audio: &mut Audio, battle_menu: &mut MenuScreen, print_damage: &mut PrintDamage) -> bool {
-    self.state.update();
+  pub fn update(&mut self, audio: &mut dyn AudioPlayer, battle_menu: &mut MenuScreen, print_damage: &mut PrintDamage, atb_rate: f64) -> bool {
+    self.state.update(atb_rate);
+    if !self.animation.is_currently_animating() && !self.state.is_turn_active() {
+      if let Some(poison_damage) = self.state.take_poison_damage() {
+        self.receive_battle_action(|state: &mut BattleState, damage: f64, _element: Option<Element>| state.apply_poison_damage(damage as u16), poison_damage as f64, None);
+      }
+    }
     if self.animation.is_currently_animating() {
       let animation_done = self.animation.advance_animation();
       if animation_done {
         match self.animation.get_current_animation() {
           Animation::StartTurn => battle_menu.set_menu(battle_menus::main_battle_menu(&self)),
-          Animation::EndTurn => {
+          Animation::EndTurn | Animation::Defend => {
             self.get_battle_state_mut().end_turn();
             return true;
           },
-          Animation::Attack | Animation::HurtSelf(_, _) => {
+          Animation::Attack | Animation::Cast | Animation::HurtSelf(_, _, _) => {
             self.animation.start_animation(Animation::EndTurn);
           },
-          Animation::Victory => return true,
+          Animation::Victory | Animation::Flee => return true,
           _ => ()
         }
       } else {
         match self.animation.get_current_animation() {
           Animation::StartTurn => self.x += 5.,
-          Animation::EndTurn   => self.x -= 14.,
+          Animation::EndTurn | Animation::Defend => self.x = (self.x - 14.).max(self.get_row_x()),
+          Animation::Flee      => self.x -= 4.,
           Animation::Attack => {
             if self.animation.get_frames_remaining() > 24 {
               self.x += 5.;
             }
           },
-          Animation::Hurt(action, incoming_damage) | Animation::HurtSelf(action, incoming_damage) => {
+          Animation::Hurt(action, incoming_damage, element) | Animation::HurtSelf(action, incoming_damage, element) => {
             if self.animation.get_frames_remaining() == 20 {
-              action(self.get_battle_state_mut(), incoming_damage);
-              print_damage.set(incoming_damage, self.x + 32., self.y + 32., [1.; 3]);
+              let affinity = element.and_then(|element: Element| self.state.get_affinity(element));
+              action(self.get_battle_state_mut(), incoming_damage, element);
+              print_damage.set(incoming_damage, self.x + 32., self.y + 32.);
+              print_damage.set_affinity(affinity);
               audio.play_sfx("physical_hit");
             }
           },
@@ -113,7 +111,9 @@ impl Character {
   }
 
   pub fn start_battle(&mut self, surprise: bool) {
-    self.x = 100.;
+    self.state.clear_battle_statuses();
+    self.state.set_guarding(false);
+    self.x = self.get_row_x();
     self.y = self.id as f32 * 80.;
     if surprise {
       self.animation.turn_character(Direction::Left);
@@ -127,15 +127,32 @@ impl Character {
     self.animation.start_animation(Animation::StartTurn);
   }
 
-  pub fn perform_battle_action(&mut self) {
-    self.animation.start_animation(Animation::Attack);
+  pub fn change_row(&mut self) {
+    self.state.toggle_row();
+    self.animation.start_animation(Animation::EndTurn);
+  }
+
+  fn get_row_x(&self) -> f32 {
+    match self.state.get_row() {
+      BattleRow::Front => FRONT_ROW_X,
+      BattleRow::Back  => BACK_ROW_X
+    }
+  }
+
+  pub fn defend(&mut self) {
+    self.state.set_guarding(true);
+    self.animation.start_animation(Animation::Defend);
   }
 
-  pub fn receive_battle_action(&mut self, action: for<'a> fn(&'a mut BattleState, f64), incoming_damage: f64) {
+  pub fn perform_battle_action(&mut self, animation: Animation) {
+    self.animation.start_animation(animation);
+  }
+
+  pub fn receive_battle_action(&mut self, action: TargetAction, incoming_damage: f64, element: Option<Element>) {
     if self.get_battle_state().is_turn_active() {
-      self.animation.start_animation(Animation::HurtSelf(action, incoming_damage));
+      self.animation.start_animation(Animation::HurtSelf(action, incoming_damage, element));
     } else {
-      self.animation.start_animation(Animation::Hurt(action, incoming_damage));
+      self.animation.start_animation(Animation::Hurt(action, incoming_damage, element));
     }
   }
 
@@ -145,6 +162,18 @@ impl Character {
     }
   }
 
+  pub fn flee(&mut self) {
+    self.animation.turn_character(Direction::Left);
+    self.animation.start_animation(Animation::Flee);
+  }
+
+  pub fn is_fleeing(&self) -> bool {
+    match self.animation.get_current_animation() {
+      Animation::Flee => self.animation.is_currently_animating(),
+      _ => false
+    }
+  }
+
   pub fn is_atb_full(&self) -> bool {
     self.state.is_atb_full()
   }
@@ -157,6 +186,19 @@ impl Character {
     self.id
   }
 
+  // ids double as battle slots, so they follow the member's place in the party
+  pub fn set_id(&mut self, id: usize) {
+    self.id = id;
+  }
+
+  pub fn is_locked(&self) -> bool {
+    self.locked
+  }
+
+  pub fn set_locked(&mut self, locked: bool) {
+    self.locked = locked;
+  }
+
   pub fn get_coords(&self) -> (f32, f32) {
     (self.x, self.y)
   }
@@ -169,24 +211,59 @@ impl Character {
     &mut self.state
   }
 
+  pub fn get_equipment(&self, slot: EquipmentSlot) -> Option<&Equipment> {
+    self.equipment[slot as usize].as_ref()
+  }
+
+  pub fn equip(&mut self, equipment: Equipment) -> Option<Equipment> {
+    let previous = self.equipment[equipment.get_slot() as usize].replace(equipment);
+    self.update_equipment_bonuses();
+    previous
+  }
+
+  pub fn unequip(&mut self, slot: EquipmentSlot) -> Option<Equipment> {
+    let previous = self.equipment[slot as usize].take();
+    self.update_equipment_bonuses();
+    previous
+  }
+
+  fn update_equipment_bonuses(&mut self) {
+    let mut bonuses = StatBonuses::default();
+    let mut affinities = Vec::new();
+    for equipment in self.equipment.iter().flatten() {
+      bonuses.add_bonuses(equipment.get_bonuses());
+      affinities.extend(equipment.get_affinities().iter().copied());
+    }
+    self.state.set_equipment_bonuses(bonuses);
+    self.state.set_equipment_affinities(affinities);
+  }
+
   pub fn get_attack_ability_as_menuitem(&self) -> MenuItem {
-    let on_click = OnClickEvent::ToTargetSelection(self.attack_ability.1, self.attack_ability.2, self.attack_ability.3);
-    MenuItem::new(self.attack_ability.0.to_owned(), 70., 468., on_click)
+    self.attack_ability.as_menuitem(&self.state, 70., 468.)
   }
 
   pub fn get_primary_ability_as_menuitem(&self) -> MenuItem {
-    MenuItem::new(self.primary_ability.0.to_owned(), 70., 500., OnClickEvent::None)
+    self.primary_ability.as_menuitem(&self.state, 70., 500.)
   }
 
   pub fn get_secondary_ability_as_menuitem(&self) -> MenuItem {
-    MenuItem::new(self.secondary_ability.0.to_owned(), 70., 532., OnClickEvent::None)
+    self.secondary_ability.as_menuitem(&self.state, 70., 532.)
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram) {
+  pub fn get_skills(&self) -> &Vec<Ability> {
+    &self.skills
+  }
+
+  pub fn draw(&self, program: &mut dyn Renderer) {
     self.animation.draw(program, self.x, self.y);
+    self.state.draw_statuses(program, self.x, self.y - 20.);
+  }
+
+  pub fn draw_portrait(&self, program: &mut dyn Renderer, x: f32, y: f32) {
+    program.draw(self.animation.get_portrait_key(), x, y, 48., 48., 1.);
   }
 
-  pub fn draw_battle_info(&self, program: &mut ShaderProgram) {
+  pub fn draw_battle_info(&self, program: &mut dyn Renderer) {
     self.state.draw(program, &self.name, self.id);
   }
 }
@@ -0,0 +1,43 @@
+use serde::Deserialize;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Element {
+  Fire, Ice, Lightning, Holy, Dark
+}
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Affinity {
+  Weak, Resist, Immune, Absorb
+}
+
+impl Affinity {
+  pub fn get_multiplier(&self) -> f64 {
+    match self {
+      Affinity::Weak   => 2.,
+      Affinity::Resist => 0.5,
+      Affinity::Immune => 0.,
+      Affinity::Absorb => -1.
+    }
+  }
+
+  pub fn get_label(&self) -> String {
+    match self {
+      Affinity::Weak   => String::from("Weak"),
+      Affinity::Resist => String::from("Resist"),
+      Affinity::Immune => String::from("Immune"),
+      Affinity::Absorb => String::from("Absorb")
+    }
+  }
+
+  // the strongest protection wins when several sources disagree
+  pub fn get_priority(&self) -> u8 {
+    match self {
+      Affinity::Weak   => 0,
+      Affinity::Resist => 1,
+      Affinity::Immune => 2,
+      Affinity::Absorb => 3
+    }
+  }
+}
@@ -1,21 +1,46 @@
 use crate::game::animation::Animation;
 use crate::game::animation::enemy::EnemyAnimation;
-use crate::game::battle::character::Character;
+use crate::game::backend::{AudioPlayer, Renderer, Rng};
+use crate::game::battle::TargetAction;
+use crate::game::battle::ai::AiRule;
+use crate::game::battle::element::Element;
 use crate::game::battle::print_damage::PrintDamage;
-use crate::game::battle::state::BattleState;
-use crate::game::menu::notification::Notification;
-use crate::webgl::audio::Audio;
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::battle::state::{BaseStats, BattleState, GrowthRates};
+use crate::game::inventory::item::Item;
 
-pub type BattleScript = for<'a, 'b, 'c> fn(&'a mut Vec<Character>, &'b mut Vec<Vec<Enemy>>, &'c mut Notification);
+// share of successful steals that go for the rare slot
+const RARE_STEAL_CHANCE: f64 = 0.125;
+
+#[derive(Clone, Default)]
+pub struct StealTable {
+  pub common: Option<Item>,
+  pub rare: Option<Item>
+}
 
 pub struct Enemy {
   animation: EnemyAnimation,
   name: String,
   id: usize,
+  x: f32,
+  y: f32,
+  width: f32,
+  height: f32,
   experience: u32,
+  gold: u32,
+  drops: Vec<(String, f64)>,
+  steal_table: StealTable,
+  stolen_from: bool,
   state: BattleState,
-  battle_script: BattleScript
+  escapable: bool,
+  turns_taken: u32,
+  ai: Vec<AiRule>
+}
+
+pub struct EnemyRewards {
+  pub experience: u32,
+  pub gold: u32,
+  pub drops: Vec<(String, f64)>,
+  pub steal_table: StealTable
 }
 
 impl Enemy {
@@ -23,35 +48,43 @@ impl Enemy {
     sprite_key: String,
     name: String,
     id: usize,
-    experience: u32,
     level: u32,
-    hp:  u16,
-    mp:  u16,
-    att: f64,
-    def: f64,
-    mag: f64,
-    int: f64,
-    res: f64,
-    agi: f64,
-    battle_script: BattleScript
+    stats: BaseStats,
+    rewards: EnemyRewards,
+    ai: Vec<AiRule>
   ) -> Self {
     Self {
       animation: EnemyAnimation::new(sprite_key),
       name,
       id,
-      experience,
-      state: BattleState::new(level, hp, 1., mp, 1., att, 1., def, 1., mag, 1., int, 1., res, 1., agi, 1.),
-      battle_script
+      x: 0.,
+      y: 0.,
+      width: 64.,
+      height: 64.,
+      experience: rewards.experience,
+      gold: rewards.gold,
+      drops: rewards.drops,
+      steal_table: rewards.steal_table,
+      stolen_from: false,
+      state: BattleState::new(level, stats, GrowthRates::default()),
+      escapable: true,
+      turns_taken: 0,
+      ai
     }
   }
 
-  pub fn update(&mut self, audio: &mut Audio, x: f32, y: f32, print_damage: &mut PrintDamage) -> u8 {
-    self.state.update();
+  pub fn update(&mut self, audio: &mut dyn AudioPlayer, print_damage: &mut PrintDamage, atb_rate: f64) -> u8 {
+    self.state.update(atb_rate);
+    if !self.animation.is_currently_animating() && !self.state.is_turn_active() {
+      if let Some(poison_damage) = self.state.take_poison_damage() {
+        self.receive_battle_action(|state: &mut BattleState, damage: f64, _element: Option<Element>| state.apply_poison_damage(damage as u16), poison_damage as f64, None);
+      }
+    }
     if self.animation.is_currently_animating() {
       let animation_done = self.animation.advance_animation();
       if animation_done {
         match self.animation.get_current_animation() {
-          Animation::Attack | Animation::HurtSelf(_, _) => {
+          Animation::Attack | Animation::HurtSelf(_, _, _) => {
             self.get_battle_state_mut().end_turn();
             return 2;
           },
@@ -69,10 +102,12 @@ impl Enemy {
               return 1;
             }
           },
-          Animation::Hurt(action, incoming_damage) | Animation::HurtSelf(action, incoming_damage) => {
+          Animation::Hurt(action, incoming_damage, element) | Animation::HurtSelf(action, incoming_damage, element) => {
             if self.animation.get_frames_remaining() == 20 {
-              action(self.get_battle_state_mut(), incoming_damage);
-              print_damage.set(incoming_damage, x - 16., y + 32., [1.; 3]);
+              let affinity = element.and_then(|element: Element| self.state.get_affinity(element));
+              action(self.get_battle_state_mut(), incoming_damage, element);
+              print_damage.set(incoming_damage, self.x - 16., self.y + self.height / 2.);
+              print_damage.set_affinity(affinity);
               audio.play_sfx("physical_hit");
             }
           },
@@ -84,15 +119,16 @@ impl Enemy {
   }
 
   pub fn start_turn(&mut self) {
+    self.turns_taken += 1;
     self.get_battle_state_mut().start_turn();
     self.animation.start_animation(Animation::Attack);
   }
 
-  pub fn receive_battle_action(&mut self, action: for<'a> fn(&'a mut BattleState, f64), incoming_damage: f64) {
+  pub fn receive_battle_action(&mut self, action: TargetAction, incoming_damage: f64, element: Option<Element>) {
     if self.get_battle_state().is_turn_active() {
-      self.animation.start_animation(Animation::HurtSelf(action, incoming_damage));
+      self.animation.start_animation(Animation::HurtSelf(action, incoming_damage, element));
     } else {
-      self.animation.start_animation(Animation::Hurt(action, incoming_damage));
+      self.animation.start_animation(Animation::Hurt(action, incoming_damage, element));
     }
   }
 
@@ -104,10 +140,58 @@ impl Enemy {
     self.id
   }
 
+  pub fn set_placement(&mut self, x: f32, y: f32, width: f32, height: f32) {
+    self.x = x;
+    self.y = y;
+    self.width = width;
+    self.height = height;
+  }
+
+  // where the targeting cursor points, level with the middle of the sprite
+  pub fn get_target_coords(&self) -> (f32, f32) {
+    (self.x, self.y + (self.height - 64.) / 2.)
+  }
+
   pub fn get_experience(&self) -> u32 {
     self.experience
   }
 
+  pub fn get_gold(&self) -> u32 {
+    self.gold
+  }
+
+  pub fn get_drops(&self) -> &Vec<(String, f64)> {
+    &self.drops
+  }
+
+  pub fn has_anything_to_steal(&self) -> bool {
+    !self.stolen_from && (self.steal_table.common.is_some() || self.steal_table.rare.is_some())
+  }
+
+  // every enemy can only be stolen from once, whichever slot the successful steal went for
+  pub fn steal(&mut self, rng: &mut dyn Rng) -> Option<Item> {
+    if !self.has_anything_to_steal() {
+      return None;
+    }
+    let item = if rng.random() < RARE_STEAL_CHANCE {
+      self.steal_table.rare.clone()
+    } else {
+      self.steal_table.common.clone()
+    };
+    if item.is_some() {
+      self.stolen_from = true;
+    }
+    item
+  }
+
+  pub fn set_escapable(&mut self, escapable: bool) {
+    self.escapable = escapable;
+  }
+
+  pub fn is_escapable(&self) -> bool {
+    self.escapable
+  }
+
   pub fn get_battle_state(&self) -> &BattleState {
     &self.state
   }
@@ -116,11 +200,20 @@ impl Enemy {
     &mut self.state
   }
 
-  pub fn get_battle_script(&self) -> BattleScript {
-    self.battle_script
+  pub fn get_turns_taken(&self) -> u32 {
+    self.turns_taken
+  }
+
+  pub fn get_ai(&self) -> &Vec<AiRule> {
+    &self.ai
   }
   
-  pub fn draw(&self, program: &mut ShaderProgram, x: f32, y: f32) {
-    self.animation.draw(program, x, y);
+  pub fn draw(&self, program: &mut dyn Renderer) {
+    self.animation.draw(program, self.x, self.y, self.width, self.height);
+    self.state.draw_statuses(program, self.x, self.y - 20.);
+  }
+
+  pub fn draw_portrait(&self, program: &mut dyn Renderer, x: f32, y: f32) {
+    program.draw(self.animation.get_portrait_key(), x, y, 48., 48., 1.);
   }
 }
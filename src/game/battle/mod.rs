@@ -1,31 +1,75 @@
+pub mod ability;
+pub mod ai;
 pub mod character;
+pub mod element;
 pub mod enemy;
 pub mod state;
+pub mod status;
 pub mod print_damage;
 
 use character::Character;
-use enemy::{BattleScript, Enemy};
+use element::Element;
+use enemy::Enemy;
 use print_damage::PrintDamage;
+use serde::{Deserialize, Serialize};
 use state::BattleState;
+use status::StatusEffect;
 
+use crate::game::backend::{AudioPlayer, Input, Renderer, Rng};
+use crate::game::config::AtbMode;
 use crate::game::data::{battle_menus, menus};
 use crate::game::menu::container::MenuContainer;
 use crate::game::menu::MenuScreen;
 use crate::game::menu::notification::Notification;
 use crate::game::transition::{Transition, TransitionStyle};
-use crate::webgl::audio::Audio;
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::GameData;
+
+// frames the defeated party stays on screen before the game over screen
+const DEFEAT_FRAMES: u32 = 90;
+const TURN_ORDER_LENGTH: usize = 8;
 
 enum FightingState {
-  Fighting, Victory, Flee, NotFighting
+  Fighting, Victory, Flee, Defeat(u32), NotFighting
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Deserialize)]
 pub enum BattleActionTargetStart {
   Enemies, Party, Myself
 }
 
-pub type ActionTuple = (for<'a, 'b> fn(&'a mut BattleState, &'b mut Notification) -> f64, for<'a> fn(&'a mut BattleState, f64));
+// how far an ability spreads from the battler it was aimed at, for enemies that aim without a menu
+#[derive(Copy, Clone, PartialEq)]
+pub enum TargetScope {
+  Single, Row, All, Random
+}
+
+#[derive(Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BattleRow {
+  #[default]
+  Front,
+  Back
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum DamageType {
+  Physical, Magical, Healing
+}
+
+#[derive(Clone, Default)]
+pub struct BattleSpoils {
+  pub experience: u32,
+  pub gold: u32,
+  pub items: Vec<String>
+}
+
+pub type TargetAction = for<'a> fn(&'a mut BattleState, f64, Option<Element>);
+
+pub type ActionTuple = (
+  for<'a, 'b, 'c> fn(&'a mut BattleState, &'b mut Notification, &'c mut dyn Rng) -> f64,
+  TargetAction,
+  DamageType
+);
 
 pub struct Battle {
   soundtrack_file: String,
@@ -33,7 +77,7 @@ pub struct Battle {
   enemies: Vec<Vec<Enemy>>,
   active_turns: Vec<usize>,
   current_turn: usize, // 0 = Noone's turn, 1-4 party member's turn, 5 >= enemy's turn
-  experience_gained: u32,
+  spoils: BattleSpoils,
   fighting_state: FightingState,
   print_damage: PrintDamage
 }
@@ -46,68 +90,93 @@ impl Battle {
       enemies: vec![Vec::new()],
       active_turns: Vec::new(),
       current_turn: 0,
-      experience_gained: 0,
+      spoils: BattleSpoils::default(),
       fighting_state: FightingState::NotFighting,
       print_damage: PrintDamage::new()
     }
   }
 
-  pub fn update(&mut self, audio: &mut Audio, party: &mut Vec<Character>, transition: &mut Transition, notification: &mut Notification) {
+  pub fn update(
+    &mut self,
+    input: &mut dyn Input,
+    audio: &mut dyn AudioPlayer,
+    rng: &mut dyn Rng,
+    transition: &mut Transition,
+    game_data: &mut GameData
+  ) {
     audio.update(&self.soundtrack_file);
-    self.start_turn(party);
+    if let FightingState::Flee | FightingState::Defeat(_) = self.fighting_state {
+      self.end_battle(audio, rng, &mut game_data.party, transition);
+      return;
+    }
+    self.start_turn(&mut game_data.party);
+    // in wait mode nobody's gauge moves while a command or target menu is open,
+    // in ctb mode nobody's gauge moves from the moment a turn comes up until it has played out
+    let atb_rate = match game_data.config.get_atb_mode() {
+      AtbMode::Wait if self.battle_menu.is_open() => 0.,
+      AtbMode::Ctb if self.current_turn != 0 || !self.active_turns.is_empty() => 0.,
+      _ => game_data.config.get_battle_speed()
+    };
     if self.battle_menu.is_open() {
-      self.battle_menu.update(audio, party, &mut self.enemies, transition, notification);
+      self.battle_menu.update(input, audio, rng, &mut self.enemies, transition, game_data);
     } else {
-      for character in party.iter_mut() {
-        if character.is_atb_full() {
-          self.active_turns.push(character.get_id());
-        }
-      }
-      for enemy in self.enemies.iter_mut().flatten() {
-        if enemy.is_atb_full() {
-          self.active_turns.push(enemy.get_id());
+      // a full gauge stays full until its turn is taken, so only queue it once
+      let ready_ids: Vec<usize> = game_data.party.iter()
+        .filter(|character: &&Character| character.is_atb_full())
+        .map(|character: &Character| character.get_id())
+        .chain(self.enemies.iter().flatten().filter(|enemy: &&Enemy| enemy.is_atb_full()).map(|enemy: &Enemy| enemy.get_id()))
+        .collect();
+      for id in ready_ids {
+        if id != self.current_turn && !self.active_turns.contains(&id) {
+          self.active_turns.push(id);
         }
       }
     }
-    for character in party.iter_mut() {
-      let turn_done = character.update(audio, &mut self.battle_menu, &mut self.print_damage);
+    for character in game_data.party.iter_mut() {
+      let turn_done = character.update(audio, &mut self.battle_menu, &mut self.print_damage, atb_rate);
       if turn_done {
         self.current_turn = 0;
       }
     }
-    let possible_battle_script = self.handle_enemy_updates(audio);
-    if let Some(battle_script) = possible_battle_script {
-      battle_script(party, &mut self.enemies, notification);
+    let enemy_acting = self.handle_enemy_updates(audio, rng, atb_rate);
+    if enemy_acting {
+      ai::perform_enemy_turn(rng, &mut game_data.party, &mut self.enemies, &mut game_data.notification);
     }
     self.print_damage.update();
-    self.end_battle(audio, party, transition);
+    self.end_battle(audio, rng, &mut game_data.party, transition);
   }
 
-  pub fn handle_enemy_updates(&mut self, audio: &mut Audio) -> Option<BattleScript> {
-    for (i, enemy_row) in self.enemies.iter_mut().enumerate() {
-      for (j, enemy) in enemy_row.iter_mut().enumerate() {
-        let turn_progression_value = enemy.update(audio, 700. + i as f32 * 100., 180. + j as f32 * 100., &mut self.print_damage);
-        match turn_progression_value {
-          1 => return Some(enemy.get_battle_script()),
-          2 => self.current_turn = 0,
-          3 => self.experience_gained += enemy.get_experience(),
-          _ => ()
-        }
+  pub fn handle_enemy_updates(&mut self, audio: &mut dyn AudioPlayer, rng: &mut dyn Rng, atb_rate: f64) -> bool {
+    for enemy in self.enemies.iter_mut().flatten() {
+      let turn_progression_value = enemy.update(audio, &mut self.print_damage, atb_rate);
+      match turn_progression_value {
+        1 => return true,
+        2 => self.current_turn = 0,
+        3 => {
+          self.spoils.experience += enemy.get_experience();
+          self.spoils.gold += enemy.get_gold();
+          for (item_name, chance) in enemy.get_drops().iter() {
+            if rng.random() < *chance {
+              self.spoils.items.push(item_name.to_owned());
+            }
+          }
+        },
+        _ => ()
       }
     }
-    None
+    false
   }
 
-  pub fn start_battle(&mut self, party: &mut Vec<Character>, enemies: Vec<Vec<Enemy>>, transition: &mut Transition) {
+  pub fn start_battle(&mut self, party: &mut [Character], enemies: Vec<Vec<Enemy>>, transition: &mut Transition) {
     for character in party.iter_mut() {
       character.start_battle(false);
     }
     self.enemies = enemies;
-    self.experience_gained = 0;
+    self.spoils = BattleSpoils::default();
     transition.set(TransitionStyle::BattleIn);
   }
 
-  fn end_battle(&mut self, audio: &mut Audio, party: &mut Vec<Character>, transition: &mut Transition) {
+  fn end_battle(&mut self, audio: &mut dyn AudioPlayer, rng: &mut dyn Rng, party: &mut [Character], transition: &mut Transition) {
     match self.fighting_state {
       FightingState::Fighting => {
         if self.enemies.iter().flatten().find(|enemy: &&Enemy| enemy.get_battle_state().get_hp() > 0).is_none() {
@@ -122,37 +191,110 @@ impl Battle {
               self.fighting_state = FightingState::Victory;
             }
           }
+        } else if alive_members_count!(party) > 0 && iter_alive_members!(party).all(|character: &mut Character| character.is_fleeing()) {
+          self.active_turns.clear();
+          self.current_turn = 0;
+          self.battle_menu.set_menu(battle_menus::none_menu());
+          for character in iter_alive_members!(party) {
+            character.get_battle_state_mut().end_turn();
+          }
+          self.fighting_state = FightingState::Flee;
+        } else if alive_members_count!(party) == 0 {
+          self.active_turns.clear();
+          self.current_turn = 0;
+          self.battle_menu.set_menu(battle_menus::none_menu());
+          self.fighting_state = FightingState::Defeat(DEFEAT_FRAMES);
         }
       },
       FightingState::Victory => {
         for character in iter_alive_members!(party) {
           character.get_battle_state_mut().end_turn();
-          let animation_done = character.update(audio, &mut self.battle_menu, &mut self.print_damage);
+          let animation_done = character.update(audio, &mut self.battle_menu, &mut self.print_damage, 0.);
+          if animation_done {
+            transition.set(TransitionStyle::BattleEndScreen(menus::battle_won, self.spoils.clone()))
+          }
+        }
+      },
+      FightingState::Flee => {
+        for character in iter_alive_members!(party) {
+          let animation_done = character.update(audio, &mut self.battle_menu, &mut self.print_damage, 0.);
           if animation_done {
-            transition.set(TransitionStyle::BattleEndScreen(menus::battle_won, self.experience_gained))
+            transition.set(TransitionStyle::BattleOut);
           }
         }
       },
-      FightingState::Flee => (),
+      FightingState::Defeat(frames_left) => {
+        // let the finishing blow play out, but nobody takes another turn
+        for character in party.iter_mut() {
+          character.update(audio, &mut self.battle_menu, &mut self.print_damage, 0.);
+        }
+        self.handle_enemy_updates(audio, rng, 0.);
+        self.print_damage.update();
+        if frames_left == 0 {
+          transition.set(TransitionStyle::BattleEndScreen(menus::game_over, BattleSpoils::default()));
+          self.fighting_state = FightingState::Defeat(DEFEAT_FRAMES);
+        } else {
+          self.fighting_state = FightingState::Defeat(frames_left - 1);
+        }
+      },
       FightingState::NotFighting => ()
     }
   }
 
-  pub fn start_turn(&mut self, party: &mut Vec<Character>) {
-    if self.active_turns.len() > 0 && self.current_turn == 0 {
+  pub fn start_turn(&mut self, party: &mut [Character]) {
+    if !self.active_turns.is_empty() && self.current_turn == 0 {
       self.current_turn = self.active_turns.remove(0);
       if self.current_turn > 0 && self.current_turn < 5 {
-        party.iter_mut().find(|character: &&mut Character| self.current_turn == character.get_id()).unwrap().start_turn();
+        let character = party.iter_mut().find(|character: &&mut Character| self.current_turn == character.get_id()).unwrap();
+        if character.get_battle_state().has_status(StatusEffect::Sleep) {
+          character.get_battle_state_mut().end_turn();
+          self.current_turn = 0;
+        } else {
+          character.start_turn();
+        }
       } else if self.current_turn > 4 {
         for enemy in self.enemies.iter_mut().flatten() {
           if enemy.get_id() == self.current_turn {
-            enemy.start_turn();
+            if enemy.get_battle_state().has_status(StatusEffect::Sleep) {
+              enemy.get_battle_state_mut().end_turn();
+              self.current_turn = 0;
+            } else {
+              enemy.start_turn();
+            }
           }
         }
       }
     }
   }
 
+  // whoever is acting, then everyone already waiting, then the predicted fills in order
+  pub fn get_turn_order(&self, party: &[Character]) -> Vec<usize> {
+    let mut turn_order = Vec::new();
+    if self.current_turn > 0 {
+      turn_order.push(self.current_turn);
+    }
+    turn_order.extend(self.active_turns.iter());
+    let states = party.iter()
+      .map(|character: &Character| (character.get_id(), character.get_battle_state()))
+      .chain(self.enemies.iter().flatten().map(|enemy: &Enemy| (enemy.get_id(), enemy.get_battle_state())));
+    let mut predicted_turns: Vec<(f64, usize)> = Vec::new();
+    for (id, state) in states {
+      let frames_until_turn = match state.get_frames_until_turn() {
+        // anyone already in line starts over from an empty gauge once their turn is taken
+        Some(_) if turn_order.contains(&id) => state.get_frames_per_turn(),
+        Some(frames) => frames,
+        None => continue
+      };
+      for i in 0..TURN_ORDER_LENGTH {
+        predicted_turns.push((frames_until_turn + i as f64 * state.get_frames_per_turn(), id));
+      }
+    }
+    predicted_turns.sort_by(|a: &(f64, usize), b: &(f64, usize)| a.0.total_cmp(&b.0));
+    turn_order.extend(predicted_turns.iter().map(|(_, id): &(f64, usize)| *id));
+    turn_order.truncate(TURN_ORDER_LENGTH);
+    turn_order
+  }
+
   pub fn set_fighting_state(&mut self, in_battle: bool) {
     if in_battle {
       self.fighting_state = FightingState::Fighting;
@@ -172,20 +314,73 @@ impl Battle {
     &mut self.enemies
   }
 
-  pub fn draw(&mut self, program: &mut ShaderProgram, party: &Vec<Character>) {
+  pub fn draw(&mut self, program: &mut dyn Renderer, party: &[Character]) {
     MenuContainer::new(300., 420., 1064., 704.).draw(program);
-    if self.battle_menu.is_open() {
-      self.battle_menu.draw(program);
+    MenuContainer::new(300., 8., 780., 80.).draw(program);
+    for (i, id) in self.get_turn_order(party).iter().enumerate() {
+      let x = 316. + i as f32 * 56.;
+      if let Some(character) = party.iter().find(|character: &&Character| character.get_id() == *id) {
+        character.draw_portrait(program, x, 16.);
+      } else if let Some(enemy) = self.enemies.iter().flatten().find(|enemy: &&Enemy| enemy.get_id() == *id) {
+        enemy.draw_portrait(program, x, 16.);
+      }
     }
     for character in party.iter() {
       character.draw(program);
       character.draw_battle_info(program);
     }
-    for (i, enemy_row) in self.enemies.iter().enumerate() {
-      for (j, enemy) in enemy_row.iter().enumerate() {
-        enemy.draw(program, 700. + i as f32 * 100., 180. + j as f32 * 100.);
-      }
+    for enemy in self.enemies.iter().flatten() {
+      enemy.draw(program);
+    }
+    if self.battle_menu.is_open() {
+      self.battle_menu.draw(program);
     }
     self.print_damage.draw(program);
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::backend::headless::{RecordingAudio, ScriptedInput};
+  use crate::game::data::content::Content;
+  use crate::game::random::SeededRng;
+
+  #[test]
+  fn ctb_queues_each_ready_battler_once() {
+    let mut game_data = GameData::new(Content::default_content());
+    let mut battle = Battle::new();
+    let mut transition = Transition::new();
+    let formation = game_data.content.create_formation("test_room_formation_2").unwrap();
+    battle.start_battle(&mut game_data.party, formation, &mut transition);
+    battle.set_fighting_state(true);
+    while game_data.config.get_atb_mode() != AtbMode::Ctb {
+      game_data.config.cycle_atb_mode();
+    }
+    // the party sleeps through it, every enemy is ready at once and the ones waiting behind the first fall asleep
+    // with a full gauge, which a sleeping battler keeps until its turn comes up
+    for character in game_data.party.iter_mut() {
+      character.get_battle_state_mut().inflict_status(StatusEffect::Sleep, u32::MAX);
+    }
+    for (i, enemy) in battle.enemies.iter_mut().flatten().enumerate() {
+      enemy.get_battle_state_mut().update_atb(1000.);
+      if i > 0 {
+        enemy.get_battle_state_mut().inflict_status(StatusEffect::Sleep, u32::MAX);
+      }
+    }
+    let mut input = ScriptedInput::new(vec![]);
+    let mut audio = RecordingAudio::new();
+    let mut rng = SeededRng::new(3);
+    for frame in 0..30 {
+      input.advance_frame();
+      battle.update(&mut input, &mut audio, &mut rng, &mut transition, &mut game_data);
+      let mut queued = battle.active_turns.clone();
+      queued.push(battle.current_turn);
+      queued.retain(|id: &usize| *id != 0);
+      let queued_count = queued.len();
+      queued.sort();
+      queued.dedup();
+      assert_eq!(queued.len(), queued_count, "a battler was queued twice on frame {}", frame);
+    }
+  }
+}
@@ -1,63 +1,88 @@
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::backend::Renderer;
+use crate::game::battle::element::Affinity;
+use crate::game::menu::font::print_text_with_opacity;
 
-pub struct PrintDamage {
+struct DamagePopup {
   value: f64,
   x: f32, 
   y: f32,
-  color: [f32; 3],
+  label: String,
   opacity: f32,
   show_time_left: f32
 }
 
+impl DamagePopup {
+  fn update(&mut self) {
+    if self.show_time_left > 0. {
+      self.show_time_left -= 1.;
+      self.y -= 0.0005 * self.show_time_left * self.show_time_left;
+      if self.show_time_left < 30. {
+        self.opacity = (self.opacity - 0.01) * self.opacity * self.opacity;
+      }
+    }
+  }
+
+  fn draw(&self, program: &mut dyn Renderer) {
+    for (index, character) in self.value.to_string().chars().enumerate() {
+      let sprite_key = match character {
+        '0' => "0",
+        '1' => "1",
+        '2' => "2",
+        '3' => "3",
+        '4' => "4",
+        '5' => "5",
+        '6' => "6",
+        '7' => "7",
+        '8' => "8",
+        '9' => "9",
+        _ => ""
+      };
+      program.draw(format!("fonts/numbers/{}", sprite_key), self.x + index as f32 * 20., self.y, 16., 24., self.opacity);
+    }
+    print_text_with_opacity(program, self.label.to_owned(), self.x, self.y + 28., self.opacity);
+  }
+}
+
+// area attacks land on several battlers in the same frame, so every hit gets its own popup
+pub struct PrintDamage {
+  popups: Vec<DamagePopup>
+}
+
 impl PrintDamage {
   pub fn new() -> Self {
     Self {
-      value: 0.,
-      x: 0., 
-      y: 0.,
-      color: [0.; 3],
-      opacity: 1.,
-      show_time_left: 0.
+      popups: Vec::new()
     }
   }
 
-  pub fn set(&mut self, value: f64, x: f32, y: f32, color: [f32; 3]) {
-    self.value = value;
-    self.x = x;
-    self.y = y;
-    self.color = color;
-    self.opacity = 1.;
-    self.show_time_left = 60.;
+  pub fn set(&mut self, value: f64, x: f32, y: f32) {
+    self.popups.push(DamagePopup {
+      value,
+      x,
+      y,
+      label: String::new(),
+      opacity: 1.,
+      show_time_left: 60.
+    });
+  }
+
+  pub fn set_affinity(&mut self, affinity: Option<Affinity>) {
+    if let (Some(affinity), Some(popup)) = (affinity, self.popups.last_mut()) {
+      popup.value = (popup.value * affinity.get_multiplier().abs()).round();
+      popup.label = affinity.get_label();
+    }
   }
 
   pub fn update(&mut self) {
-    if self.show_time_left > 0. {
-      self.show_time_left -= 1.;
-      self.y -= 0.0005 * self.show_time_left * self.show_time_left;
-      if self.show_time_left < 30. {
-        self.opacity = (self.opacity - 0.01) * self.opacity * self.opacity;
-      }
+    for popup in self.popups.iter_mut() {
+      popup.update();
     }
+    self.popups.retain(|popup: &DamagePopup| popup.show_time_left > 0.);
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram) {
-    if self.show_time_left > 0. {
-      for (index, character) in self.value.to_string().chars().enumerate() {
-        let sprite_key = match character {
-          '0' => "0",
-          '1' => "1",
-          '2' => "2",
-          '3' => "3",
-          '4' => "4",
-          '5' => "5",
-          '6' => "6",
-          '7' => "7",
-          '8' => "8",
-          '9' => "9",
-          _ => ""
-        };
-        program.draw(format!("fonts/numbers/{}", sprite_key), self.x + index as f32 * 20., self.y, 16., 24., self.opacity);
-      }
+  pub fn draw(&self, program: &mut dyn Renderer) {
+    for popup in self.popups.iter() {
+      popup.draw(program);
     }
   }
 }
@@ -1,70 +1,159 @@
-use crate::game::menu::font::print_text;
-use crate::webgl::shader_program::ShaderProgram;
+use serde::Deserialize;
+use crate::game::backend::{Renderer, Rng};
+use crate::game::battle::BattleRow;
+use crate::game::battle::element::{Affinity, Element};
+use crate::game::battle::status::{StatusChange, StatusEffect};
+use crate::game::inventory::equipment::StatBonuses;
+use crate::game::menu::font::{print_text, print_text_with_opacity};
+
+const MAX_HP: u16 = 9999;
+const MAX_MP: u16 = 999;
+// status durations count down once every this many frames
+const STATUS_TICK_FRAMES: f64 = 60.;
+// growth rates scale these per-level gains
+const HP_PER_LEVEL: f64 = 12.;
+const MP_PER_LEVEL: f64 = 3.;
+const STAT_PER_LEVEL: f64 = 1.;
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BaseStats {
+  pub hp:  u16,
+  pub mp:  u16,
+  pub att: f64,
+  pub def: f64,
+  pub mag: f64,
+  pub int: f64,
+  pub res: f64,
+  pub agi: f64
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GrowthRates {
+  pub hp:  f32,
+  pub mp:  f32,
+  pub att: f32,
+  pub def: f32,
+  pub mag: f32,
+  pub int: f32,
+  pub res: f32,
+  pub agi: f32
+}
+
+// every stat grows at the base rate
+impl Default for GrowthRates {
+  fn default() -> Self {
+    Self { hp: 1., mp: 1., att: 1., def: 1., mag: 1., int: 1., res: 1., agi: 1. }
+  }
+}
+
+#[derive(Default)]
+pub struct LevelUpGains {
+  pub levels: u32,
+  pub hp: u16,
+  pub mp: u16,
+  pub att: f64,
+  pub def: f64,
+  pub mag: f64,
+  pub int: f64,
+  pub res: f64,
+  pub agi: f64
+}
+
+impl LevelUpGains {
+  pub fn add_gains(&mut self, other: &LevelUpGains) {
+    self.levels += other.levels;
+    self.hp += other.hp;
+    self.mp += other.mp;
+    self.att += other.att;
+    self.def += other.def;
+    self.mag += other.mag;
+    self.int += other.int;
+    self.res += other.res;
+    self.agi += other.agi;
+  }
+
+  pub fn get_summary(&self) -> String {
+    // short enough to sit under a name on the victory screen
+    format!("HP+{} MP+{} ATT+{:.0}", self.hp, self.mp, self.att)
+  }
+}
 
 pub struct BattleState {
   level: u32,
   experience: u32,
   hp: u16,
-  hp_growth_rate: f32,
+  max_hp: u16,
   mp: u16,
-  mp_growth_rate: f32,
+  max_mp: u16,
   att: f64,
-  att_growth_rate: f32,
   def: f64,
-  def_growth_rate: f32,
   mag: f64,
-  mag_growth_rate: f32,
   int: f64,
-  int_growth_rate: f32,
   res: f64,
-  res_growth_rate: f32,
   agi: f64,
-  agi_growth_rate: f32,
+  growth_rates: GrowthRates,
+  equipment_bonuses: StatBonuses,
+  statuses: Vec<(StatusEffect, u32)>,
+  immunities: Vec<StatusEffect>,
+  affinities: Vec<(Element, Affinity)>,
+  equipment_affinities: Vec<(Element, Affinity)>,
+  status_frames: f64,
+  poison_damage_due: bool,
+  guarding: bool,
+  row: BattleRow,
   atb: u8,
   atb_subtick: f64,
   is_turn_active: bool
 }
 
 impl BattleState {
-  pub fn new(
-    level: u32,
-    hp:  u16, hp_growth_rate:  f32,
-    mp:  u16, mp_growth_rate:  f32,
-    att: f64, att_growth_rate: f32,
-    def: f64, def_growth_rate: f32,
-    mag: f64, mag_growth_rate: f32,
-    int: f64, int_growth_rate: f32,
-    res: f64, res_growth_rate: f32,
-    agi: f64, agi_growth_rate: f32
-  ) -> Self {
+  pub fn new(level: u32, stats: BaseStats, growth_rates: GrowthRates) -> Self {
     Self {
       level,
       experience: 0,
-      hp,  hp_growth_rate,
-      mp,  mp_growth_rate,
-      att, att_growth_rate,
-      def, def_growth_rate,
-      mag, mag_growth_rate,
-      int, int_growth_rate,
-      res, res_growth_rate,
-      agi, agi_growth_rate,
+      hp:  stats.hp,
+      max_hp: stats.hp,
+      mp:  stats.mp,
+      max_mp: stats.mp,
+      att: stats.att,
+      def: stats.def,
+      mag: stats.mag,
+      int: stats.int,
+      res: stats.res,
+      agi: stats.agi,
+      growth_rates,
+      equipment_bonuses: StatBonuses::default(),
+      statuses: Vec::new(),
+      immunities: Vec::new(),
+      affinities: Vec::new(),
+      equipment_affinities: Vec::new(),
+      status_frames: 0.,
+      poison_damage_due: false,
+      guarding: false,
+      row: BattleRow::Front,
       atb: 0,
       atb_subtick: 0.,
       is_turn_active: false
     }
   }
 
-  pub fn update(&mut self) {
+  pub fn update(&mut self, atb_rate: f64) {
     if self.get_hp() > 0 {
-      self.update_atb();
+      if !self.has_status(StatusEffect::Sleep) {
+        self.update_atb(atb_rate);
+      }
+      self.update_statuses(atb_rate);
     }
   }
 
-  pub fn update_atb(&mut self) {
-    if self.is_atb_full() {
+  pub fn update_atb(&mut self, atb_rate: f64) {
+    // a gauge that filled while time was stopped keeps its turn until time moves again
+    if self.is_atb_full() && atb_rate > 0. {
       self.atb = 0;
     }
-    self.atb_subtick += self.agi;
+    self.atb_subtick += self.get_atb_fill() * atb_rate;
     while self.atb_subtick > 4. {
       self.atb_subtick -= 5.;
       if let Some(atb) = self.atb.checked_add(1) {
@@ -73,8 +162,145 @@ impl BattleState {
     }
   }
 
+  fn get_atb_fill(&self) -> f64 {
+    let speed_modifier = if self.has_status(StatusEffect::Haste) {
+      1.5
+    } else if self.has_status(StatusEffect::Slow) {
+      0.5
+    } else {
+      1.
+    };
+    self.get_total_agility_stat() * speed_modifier
+  }
+
+  // frames at normal speed until the gauge next fills, None if it isn't moving at all
+  pub fn get_frames_until_turn(&self) -> Option<f64> {
+    if self.get_hp() == 0 || self.has_status(StatusEffect::Sleep) {
+      return None;
+    }
+    let subticks_left = ((u8::MAX - self.atb) as f64 * 5. - self.atb_subtick).max(0.);
+    Some(subticks_left / self.get_atb_fill())
+  }
+
+  pub fn get_frames_per_turn(&self) -> f64 {
+    u8::MAX as f64 * 5. / self.get_atb_fill()
+  }
+
+  // statuses run on battle time, so they stop with the gauges and follow the battle speed
+  fn update_statuses(&mut self, atb_rate: f64) {
+    if atb_rate <= 0. {
+      return;
+    }
+    self.status_frames += atb_rate;
+    if self.status_frames < STATUS_TICK_FRAMES {
+      return;
+    }
+    self.status_frames -= STATUS_TICK_FRAMES;
+    if self.has_status(StatusEffect::Poison) {
+      self.poison_damage_due = true;
+    }
+    for (_, duration) in self.statuses.iter_mut() {
+      *duration -= 1;
+    }
+    self.statuses.retain(|(_, duration): &(StatusEffect, u32)| *duration > 0);
+  }
+
+  pub fn has_status(&self, status: StatusEffect) -> bool {
+    self.statuses.iter().any(|(active_status, _): &(StatusEffect, u32)| *active_status == status)
+  }
+
+  pub fn get_statuses(&self) -> &Vec<(StatusEffect, u32)> {
+    &self.statuses
+  }
+
+  pub fn set_immunities(&mut self, immunities: Vec<StatusEffect>) {
+    self.immunities = immunities;
+  }
+
+  pub fn inflict_status(&mut self, status: StatusEffect, duration: u32) -> bool {
+    if self.hp == 0 || duration == 0 || self.immunities.contains(&status) {
+      return false;
+    }
+    match status {
+      StatusEffect::Haste => self.cure_status(StatusEffect::Slow),
+      StatusEffect::Slow  => self.cure_status(StatusEffect::Haste),
+      _ => ()
+    };
+    self.cure_status(status);
+    self.statuses.push((status, duration));
+    true
+  }
+
+  pub fn cure_status(&mut self, status: StatusEffect) {
+    self.statuses.retain(|(active_status, _): &(StatusEffect, u32)| *active_status != status);
+    if status == StatusEffect::Poison {
+      self.poison_damage_due = false;
+    }
+  }
+
+  pub fn apply_status_change(&mut self, status_change: &StatusChange, rng: &mut dyn Rng) -> bool {
+    match status_change {
+      StatusChange::Inflict { status, duration, chance } => rng.random() < *chance && self.inflict_status(*status, *duration),
+      StatusChange::Cure { statuses } => {
+        let cured = statuses.iter().any(|status: &StatusEffect| self.has_status(*status));
+        for status in statuses.iter() {
+          self.cure_status(*status);
+        }
+        cured
+      }
+    }
+  }
+
+  pub fn clear_battle_statuses(&mut self) {
+    self.statuses.retain(|(status, _): &(StatusEffect, u32)| !status.is_battle_only());
+    self.status_frames = 0.;
+  }
+
+  pub fn restore_statuses(&mut self, statuses: Vec<(StatusEffect, u32)>) {
+    self.statuses.clear();
+    for (status, duration) in statuses.into_iter() {
+      self.inflict_status(status, duration);
+    }
+  }
+
+  pub fn take_poison_damage(&mut self) -> Option<u16> {
+    if !self.poison_damage_due || self.hp == 0 {
+      return None;
+    }
+    self.poison_damage_due = false;
+    Some((self.get_max_hp() / 16).max(1))
+  }
+
+  pub fn apply_poison_damage(&mut self, value: u16) {
+    self.hp = self.hp.saturating_sub(value);
+  }
+
   pub fn start_turn(&mut self) {
     self.is_turn_active = true;
+    self.guarding = false;
+  }
+
+  pub fn set_guarding(&mut self, guarding: bool) {
+    self.guarding = guarding;
+  }
+
+  pub fn is_guarding(&self) -> bool {
+    self.guarding
+  }
+
+  pub fn get_row(&self) -> BattleRow {
+    self.row
+  }
+
+  pub fn set_row(&mut self, row: BattleRow) {
+    self.row = row;
+  }
+
+  pub fn toggle_row(&mut self) {
+    self.row = match self.row {
+      BattleRow::Front => BattleRow::Back,
+      BattleRow::Back  => BattleRow::Front
+    };
   }
 
   pub fn end_turn(&mut self) {
@@ -83,16 +309,81 @@ impl BattleState {
     self.atb_subtick = 0.;
   }
 
+  // max_stats holds the saved maximum hp and mp alongside the other stats
+  pub fn restore(&mut self, level: u32, experience: u32, hp: u16, mp: u16, max_stats: BaseStats) {
+    self.level = level;
+    self.experience = experience;
+    self.max_hp = max_stats.hp.min(MAX_HP);
+    self.max_mp = max_stats.mp.min(MAX_MP);
+    self.hp = hp.min(self.get_max_hp());
+    self.mp = mp.min(self.get_max_mp());
+    self.att = max_stats.att;
+    self.def = max_stats.def;
+    self.mag = max_stats.mag;
+    self.int = max_stats.int;
+    self.res = max_stats.res;
+    self.agi = max_stats.agi;
+  }
+
+  pub fn get_level(&self) -> u32 {
+    self.level
+  }
+
   pub fn get_experience(&self) -> u32 {
     self.experience
   }
 
-  pub fn add_experience(&mut self, experience: u32) {
+  pub fn add_experience(&mut self, experience: u32, mut rng: Option<&mut (dyn Rng + '_)>) -> LevelUpGains {
+    let mut gains = LevelUpGains::default();
     self.experience += experience;
     while self.experience >= self.level * self.level * 150 - 100 {
       self.experience -= self.level * self.level * 150 - 100;
-      self.level += 1;
+      gains.add_gains(&self.level_up(rng.as_deref_mut()));
+    }
+    gains
+  }
+
+  pub fn set_level(&mut self, level: u32, mut rng: Option<&mut (dyn Rng + '_)>) -> LevelUpGains {
+    let mut gains = LevelUpGains::default();
+    while self.level < level {
+      gains.add_gains(&self.level_up(rng.as_deref_mut()));
     }
+    self.experience = 0;
+    gains
+  }
+
+  // without an rng every level grows by exactly the growth rate
+  pub fn level_up(&mut self, mut rng: Option<&mut (dyn Rng + '_)>) -> LevelUpGains {
+    let mut roll = |rate: f32, per_level: f64| -> f64 {
+      let variance = match rng.as_deref_mut() {
+        Some(rng) => 0.8 + rng.random() * 0.4,
+        None => 1.
+      };
+      rate as f64 * per_level * variance
+    };
+    let gains = LevelUpGains {
+      levels: 1,
+      hp:  roll(self.growth_rates.hp, HP_PER_LEVEL).round().min((MAX_HP - self.max_hp) as f64) as u16,
+      mp:  roll(self.growth_rates.mp, MP_PER_LEVEL).round().min((MAX_MP - self.max_mp) as f64) as u16,
+      att: roll(self.growth_rates.att, STAT_PER_LEVEL),
+      def: roll(self.growth_rates.def, STAT_PER_LEVEL),
+      mag: roll(self.growth_rates.mag, STAT_PER_LEVEL),
+      int: roll(self.growth_rates.int, STAT_PER_LEVEL),
+      res: roll(self.growth_rates.res, STAT_PER_LEVEL),
+      agi: roll(self.growth_rates.agi, STAT_PER_LEVEL)
+    };
+    self.level += 1;
+    self.max_hp += gains.hp;
+    self.max_mp += gains.mp;
+    self.hp = (self.hp + gains.hp).min(self.get_max_hp());
+    self.mp = (self.mp + gains.mp).min(self.get_max_mp());
+    self.att += gains.att;
+    self.def += gains.def;
+    self.mag += gains.mag;
+    self.int += gains.int;
+    self.res += gains.res;
+    self.agi += gains.agi;
+    gains
   }
 
   pub fn is_turn_active(&self) -> bool {
@@ -100,14 +391,41 @@ impl BattleState {
   }
 
   pub fn is_atb_full(&self) -> bool {
-    self.atb == std::u8::MAX
+    self.atb == u8::MAX
   }
 
   pub fn get_hp(&self) -> u16 {
     self.hp
   }
 
+  pub fn get_mp(&self) -> u16 {
+    self.mp
+  }
+
+  pub fn get_base_max_hp(&self) -> u16 {
+    self.max_hp
+  }
+
+  pub fn get_base_max_mp(&self) -> u16 {
+    self.max_mp
+  }
+
+  pub fn get_max_hp(&self) -> u16 {
+    self.max_hp.saturating_add(self.equipment_bonuses.hp).min(MAX_HP)
+  }
+
+  pub fn get_max_mp(&self) -> u16 {
+    self.max_mp.saturating_add(self.equipment_bonuses.mp).min(MAX_MP)
+  }
+
+  pub fn is_low_hp(&self) -> bool {
+    self.hp > 0 && self.hp <= self.get_max_hp() / 4
+  }
+
   pub fn reduce_hp(&mut self, value: u16) {
+    if value > 0 {
+      self.cure_status(StatusEffect::Sleep);
+    }
     if let Some(new_hp) = self.hp.checked_sub(value) {
       self.hp = new_hp;
     } else {
@@ -115,6 +433,30 @@ impl BattleState {
     }
   }
 
+  pub fn can_restore_hp(&self) -> bool {
+    self.hp > 0 && self.hp < self.get_max_hp()
+  }
+
+  pub fn can_restore_mp(&self) -> bool {
+    self.hp > 0 && self.mp < self.get_max_mp()
+  }
+
+  pub fn restore_hp(&mut self, value: u16) {
+    if self.hp > 0 {
+      self.hp = self.hp.saturating_add(value).min(self.get_max_hp());
+    }
+  }
+
+  pub fn restore_mp(&mut self, value: u16) {
+    if self.hp > 0 {
+      self.mp = self.mp.saturating_add(value).min(self.get_max_mp());
+    }
+  }
+
+  pub fn reduce_mp(&mut self, value: u16) {
+    self.mp = self.mp.saturating_sub(value);
+  }
+
   pub fn get_attack_stat(&self) -> f64 {
     self.att
   }
@@ -123,11 +465,119 @@ impl BattleState {
     self.def
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram, name: &String, id: usize) {
+  pub fn get_magic_stat(&self) -> f64 {
+    self.mag
+  }
+
+  pub fn get_intelligence_stat(&self) -> f64 {
+    self.int
+  }
+
+  pub fn get_resistance_stat(&self) -> f64 {
+    self.res
+  }
+
+  pub fn get_agility_stat(&self) -> f64 {
+    self.agi
+  }
+
+  pub fn set_equipment_bonuses(&mut self, equipment_bonuses: StatBonuses) {
+    self.equipment_bonuses = equipment_bonuses;
+    self.hp = self.hp.min(self.get_max_hp());
+    self.mp = self.mp.min(self.get_max_mp());
+  }
+
+  pub fn set_affinities(&mut self, affinities: Vec<(Element, Affinity)>) {
+    self.affinities = affinities;
+  }
+
+  pub fn set_equipment_affinities(&mut self, equipment_affinities: Vec<(Element, Affinity)>) {
+    self.equipment_affinities = equipment_affinities;
+  }
+
+  pub fn get_affinity(&self, element: Element) -> Option<Affinity> {
+    self.affinities.iter().chain(self.equipment_affinities.iter())
+      .filter(|(affinity_element, _): &&(Element, Affinity)| *affinity_element == element)
+      .map(|(_, affinity): &(Element, Affinity)| *affinity)
+      .max_by_key(|affinity: &Affinity| affinity.get_priority())
+  }
+
+  pub fn get_total_attack_stat(&self) -> f64 {
+    self.att + self.equipment_bonuses.att
+  }
+
+  pub fn get_total_defence_stat(&self) -> f64 {
+    (self.def + self.equipment_bonuses.def).max(1.)
+  }
+
+  pub fn get_total_magic_stat(&self) -> f64 {
+    self.mag + self.equipment_bonuses.mag
+  }
+
+  pub fn get_total_intelligence_stat(&self) -> f64 {
+    self.int
+  }
+
+  pub fn get_total_resistance_stat(&self) -> f64 {
+    (self.res + self.equipment_bonuses.res).max(1.)
+  }
+
+  pub fn get_total_agility_stat(&self) -> f64 {
+    (self.agi + self.equipment_bonuses.agi).max(1.)
+  }
+
+  pub fn draw_statuses(&self, program: &mut dyn Renderer, x: f32, y: f32) {
+    if self.hp == 0 {
+      return;
+    }
+    for (i, (status, _)) in self.statuses.iter().enumerate() {
+      program.draw(status.get_icon_key(), x + i as f32 * 26., y, 24., 24., 1.);
+    }
+  }
+
+  pub fn draw(&self, program: &mut dyn Renderer, name: &String, id: usize) {
     print_text(program, name.to_owned(),            330.,                                                    384. + id as f32 * 62.);
-    print_text(program, format!("{}/",    self.hp), 560. + (5 - format!("{}/", self.hp).len()) as f32 * 20., 384. + id as f32 * 62.);
-    print_text(program, format!("{} HP" , self.hp), 660. + (4 - format!("{}" , self.hp).len()) as f32 * 20., 384. + id as f32 * 62.);
+    let (max_hp, max_mp) = (self.get_max_hp(), self.get_max_mp());
+    // the font has no colours, so low hp is dimmed instead
+    let hp_opacity = if self.is_low_hp() { 0.5 } else { 1. };
+    print_text_with_opacity(program, format!("{}/", self.hp), 560. + (5 - format!("{}/", self.hp).len()) as f32 * 20., 384. + id as f32 * 62., hp_opacity);
+    print_text(program, format!("{} HP" , max_hp), 660. + (4 - format!("{}" , max_hp).len()) as f32 * 20., 384. + id as f32 * 62.);
     print_text(program, format!("{}/",    self.mp), 834. + (4 - format!("{}/", self.mp).len()) as f32 * 20., 384. + id as f32 * 62.);
-    print_text(program, format!("{} MP" , self.mp), 930. + (3 - format!("{}" , self.mp).len()) as f32 * 20., 384. + id as f32 * 62.);
+    print_text(program, format!("{} MP" , max_mp), 930. + (3 - format!("{}" , max_mp).len()) as f32 * 20., 384. + id as f32 * 62.);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::battle::character::Character;
+  use crate::game::data::content::Content;
+
+  fn poisoned_character() -> Character {
+    let mut character = Content::default_content().create_starting_party().remove(0);
+    character.get_battle_state_mut().inflict_status(StatusEffect::Poison, 3);
+    character
+  }
+
+  #[test]
+  fn statuses_hold_while_time_is_stopped() {
+    let mut character = poisoned_character();
+    let state = character.get_battle_state_mut();
+    for _ in 0..600 {
+      state.update(0.);
+    }
+    assert!(state.get_statuses() == &vec![(StatusEffect::Poison, 3)]);
+    assert!(state.take_poison_damage().is_none());
+  }
+
+  #[test]
+  fn statuses_follow_the_battle_speed() {
+    let mut character = poisoned_character();
+    let state = character.get_battle_state_mut();
+    for _ in 0..60 {
+      state.update(2.);
+    }
+    assert!(state.get_statuses() == &vec![(StatusEffect::Poison, 1)]);
+    assert!(state.take_poison_damage().is_some());
   }
 }
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusEffect {
+  Poison, Sleep, Slow, Haste, Silence
+}
+
+impl StatusEffect {
+  pub fn get_short_name(&self) -> String {
+    match self {
+      StatusEffect::Poison  => String::from("PSN"),
+      StatusEffect::Sleep   => String::from("SLP"),
+      StatusEffect::Slow    => String::from("SLW"),
+      StatusEffect::Haste   => String::from("HST"),
+      StatusEffect::Silence => String::from("SIL")
+    }
+  }
+
+  pub fn get_icon_key(&self) -> String {
+    match self {
+      StatusEffect::Poison  => String::from("status_effects/poison"),
+      StatusEffect::Sleep   => String::from("status_effects/sleep"),
+      StatusEffect::Slow    => String::from("status_effects/slow"),
+      StatusEffect::Haste   => String::from("status_effects/haste"),
+      StatusEffect::Silence => String::from("status_effects/silence")
+    }
+  }
+
+  // only poison lingers once the battle is over
+  pub fn is_battle_only(&self) -> bool {
+    *self != StatusEffect::Poison
+  }
+}
+
+fn always() -> f64 {
+  1.
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum StatusChange {
+  Inflict {
+    status: StatusEffect,
+    duration: u32,
+    #[serde(default = "always")]
+    chance: f64
+  },
+  Cure {
+    statuses: Vec<StatusEffect>
+  }
+}
@@ -0,0 +1,52 @@
+// multipliers the battle speed setting cycles through
+const BATTLE_SPEEDS: [f64; 5] = [0.5, 0.75, 1., 1.5, 2.];
+const DEFAULT_BATTLE_SPEED: usize = 2;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AtbMode {
+  Active, Wait, Ctb
+}
+
+impl AtbMode {
+  pub fn get_name(&self) -> String {
+    match self {
+      AtbMode::Active => String::from("Active"),
+      AtbMode::Wait   => String::from("Wait"),
+      AtbMode::Ctb    => String::from("CTB")
+    }
+  }
+}
+
+pub struct Config {
+  atb_mode: AtbMode,
+  battle_speed: usize
+}
+
+impl Config {
+  pub fn new() -> Self {
+    Self {
+      atb_mode: AtbMode::Active,
+      battle_speed: DEFAULT_BATTLE_SPEED
+    }
+  }
+
+  pub fn get_atb_mode(&self) -> AtbMode {
+    self.atb_mode
+  }
+
+  pub fn cycle_atb_mode(&mut self) {
+    self.atb_mode = match self.atb_mode {
+      AtbMode::Active => AtbMode::Wait,
+      AtbMode::Wait   => AtbMode::Ctb,
+      AtbMode::Ctb    => AtbMode::Active
+    };
+  }
+
+  pub fn get_battle_speed(&self) -> f64 {
+    BATTLE_SPEEDS[self.battle_speed]
+  }
+
+  pub fn cycle_battle_speed(&mut self) {
+    self.battle_speed = (self.battle_speed + 1) % BATTLE_SPEEDS.len();
+  }
+}
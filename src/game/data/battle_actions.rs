@@ -1,18 +1,99 @@
+use crate::game::backend::Rng;
+use crate::game::battle::{ActionTuple, BattleRow, DamageType};
+use crate::game::battle::element::Element;
 use crate::game::battle::state::BattleState;
 use crate::game::menu::notification::Notification;
-use js_sys::Math::{floor, random};
 
-fn random_in_range(start: f64, end: f64) -> f64 {
-  floor(random() * (end - start) + start)
+fn random_in_range(rng: &mut dyn Rng, start: f64, end: f64) -> f64 {
+  (rng.random() * (end - start) + start).floor()
 }
 
-pub fn physical_attack() -> (for<'a, 'b> fn(&'a mut BattleState, &'b mut Notification) -> f64, for<'a> fn(&'a mut BattleState, f64)) {
-  let action_for_actor = |actor_state: &mut BattleState, notification: &mut Notification| -> f64 {
-    notification.set_notification(String::from("Attack"));
-    actor_state.get_attack_stat() * random_in_range(9., 11.)
+// physical blows are halved when either side stands in the back row
+fn row_modifier(state: &BattleState) -> f64 {
+  match state.get_row() {
+    BattleRow::Front => 1.,
+    BattleRow::Back  => 0.5
+  }
+}
+
+// intelligence sharpens every spell on top of raw magic
+fn spell_power(actor_state: &BattleState) -> f64 {
+  actor_state.get_total_magic_stat() + actor_state.get_total_intelligence_stat() * 0.5
+}
+
+fn guarded(target_state: &BattleState, damage: f64) -> f64 {
+  if target_state.is_guarding() {
+    damage / 2.
+  } else {
+    damage
+  }
+}
+
+fn elemental(target_state: &BattleState, damage: f64, element: Option<Element>) -> f64 {
+  match element.and_then(|element: Element| target_state.get_affinity(element)) {
+    Some(affinity) => damage * affinity.get_multiplier(),
+    None => damage
+  }
+}
+
+// absorbed hits come through as negative damage and heal instead
+fn apply_damage(target_state: &mut BattleState, damage: f64) {
+  if damage < 0. {
+    target_state.restore_hp((-damage as u16).min(9999));
+  } else {
+    target_state.reduce_hp((damage as u16).min(9999));
+  }
+}
+
+pub fn physical_attack() -> ActionTuple {
+  let action_for_actor = |actor_state: &mut BattleState, _notification: &mut Notification, rng: &mut dyn Rng| -> f64 {
+    actor_state.get_total_attack_stat() * random_in_range(rng, 9., 11.) * row_modifier(actor_state)
+  };
+  let action_for_target = |target_state: &mut BattleState, incoming_damage: f64, element: Option<Element>| {
+    let damage = guarded(target_state, incoming_damage * 5. / target_state.get_total_defence_stat()) * row_modifier(target_state);
+    apply_damage(target_state, elemental(target_state, damage, element))
+  };
+  (action_for_actor, action_for_target, DamageType::Physical)
+}
+
+pub fn ranged_attack() -> ActionTuple {
+  let action_for_actor = |actor_state: &mut BattleState, _notification: &mut Notification, rng: &mut dyn Rng| -> f64 {
+    actor_state.get_total_attack_stat() * random_in_range(rng, 9., 11.)
+  };
+  let action_for_target = |target_state: &mut BattleState, incoming_damage: f64, element: Option<Element>| {
+    let damage = guarded(target_state, incoming_damage * 5. / target_state.get_total_defence_stat());
+    apply_damage(target_state, elemental(target_state, damage, element))
+  };
+  (action_for_actor, action_for_target, DamageType::Physical)
+}
+
+pub fn magic_attack() -> ActionTuple {
+  let action_for_actor = |actor_state: &mut BattleState, _notification: &mut Notification, rng: &mut dyn Rng| -> f64 {
+    spell_power(actor_state) * random_in_range(rng, 9., 11.)
   };
-  let action_for_target = |target_state: &mut BattleState, incoming_damage: f64| {
-    target_state.reduce_hp(((incoming_damage * 5. / target_state.get_defence_stat()) as u16).min(9999))
+  let action_for_target = |target_state: &mut BattleState, incoming_damage: f64, element: Option<Element>| {
+    let damage = guarded(target_state, incoming_damage * 5. / target_state.get_total_resistance_stat());
+    apply_damage(target_state, elemental(target_state, damage, element))
   };
-  (action_for_actor, action_for_target)
+  (action_for_actor, action_for_target, DamageType::Magical)
+}
+
+pub fn heal() -> ActionTuple {
+  let action_for_actor = |actor_state: &mut BattleState, _notification: &mut Notification, rng: &mut dyn Rng| -> f64 {
+    spell_power(actor_state) * random_in_range(rng, 20., 31.) / 10.
+  };
+  let action_for_target = |target_state: &mut BattleState, incoming_heal: f64, _element: Option<Element>| {
+    target_state.restore_hp(incoming_heal as u16)
+  };
+  (action_for_actor, action_for_target, DamageType::Healing)
+}
+
+pub fn get_action(name: &str) -> Option<ActionTuple> {
+  match name {
+    "physical_attack" => Some(physical_attack()),
+    "ranged_attack"   => Some(ranged_attack()),
+    "magic_attack"    => Some(magic_attack()),
+    "heal"            => Some(heal()),
+    _ => None
+  }
 }
@@ -1,42 +1,76 @@
-use crate::game::battle::ActionTuple;
-use crate::game::battle::BattleActionTargetStart;
+use crate::game::animation::Animation;
+use crate::game::backend::Rng;
+use crate::game::battle::{BattleActionTargetStart, TargetScope};
+use crate::game::battle::ability::Ability;
 use crate::game::battle::character::Character;
 use crate::game::battle::enemy::Enemy;
+use crate::game::battle::state::BattleState;
+use crate::game::inventory::Inventory;
 use crate::game::menu::click_event::OnClickEvent;
 use crate::game::menu::container::MenuContainer;
 use crate::game::menu::item::MenuItem;
 use crate::game::menu::{MenuMovement, MenuScreen};
 use crate::game::menu::notification::Notification;
 
+pub type TargetingMenu = for<'r, 's> fn(&'r [Character], &'s mut [Vec<Enemy>], Ability) -> MenuScreen;
+
+pub type BattleActionFunction = for<'a, 'b, 'c, 'd, 'e> fn(
+  &'a mut dyn Rng,
+  &'b mut [Character],
+  &'c mut [Vec<Enemy>],
+  &'d mut Inventory,
+  Vec<usize>,
+  Ability,
+  &'e mut Notification
+);
+
 pub fn none_menu() -> MenuScreen {
   MenuScreen::new(Vec::new(), Vec::new(), Vec::new(), MenuMovement::Grid, 0, 0, OnClickEvent::None)
 }
 
 pub fn main_battle_menu(character_in_turn: &Character) -> MenuScreen {
+  let mut skill_menuitem = MenuItem::new(String::from("Skill"), 70., 564., OnClickEvent::SetBattleMenu(skill_menu));
+  skill_menuitem.set_enabled(!character_in_turn.get_skills().is_empty());
   let selectables = vec![
     vec![character_in_turn.get_attack_ability_as_menuitem()],
     vec![character_in_turn.get_primary_ability_as_menuitem()],
     vec![character_in_turn.get_secondary_ability_as_menuitem()],
-    vec![MenuItem::new(String::from("Item"),   70., 564., OnClickEvent::None)],
-    vec![MenuItem::new(String::from("Defend"), 70., 596., OnClickEvent::None)],
-    vec![MenuItem::new(String::from("Row"),    70., 628., OnClickEvent::None)]
+    vec![skill_menuitem],
+    vec![MenuItem::new(String::from("Item"),   70., 596., OnClickEvent::SetMenu(item_menu))],
+    vec![MenuItem::new(String::from("Defend"), 70., 628., OnClickEvent::Defend)],
+    vec![MenuItem::new(String::from("Row"),    70., 660., OnClickEvent::ChangeRow)]
   ];
   MenuScreen::new(vec![MenuContainer::new(16., 420., 250., 704.)], selectables, Vec::new(), MenuMovement::Grid, 0, 0, OnClickEvent::None)
 }
 
-pub fn single_target_targeting_everyone(
-  party: &Vec<Character>,
-  enemies: &mut Vec<Vec<Enemy>>,
-  action_effects: ActionTuple,
-  targeting_start_type: BattleActionTargetStart
-) -> MenuScreen {
+pub fn skill_menu(character_in_turn: &Character) -> MenuScreen {
   let mut selectables = Vec::new();
-  push_party_to_selectables(&mut selectables, party, action_effects);
-  push_enemies_to_selectables(&mut selectables, enemies, action_effects);
+  let mut unselectables = Vec::new();
+  for (i, skill) in character_in_turn.get_skills().iter().enumerate() {
+    let y = 468. + i as f32 * 32.;
+    selectables.push(vec![skill.as_menuitem(character_in_turn.get_battle_state(), 70., y)]);
+    unselectables.push(MenuItem::new(format!("{:>3}", skill.get_mp_cost()), 420., y, OnClickEvent::None));
+  }
+  MenuScreen::new(
+    vec![MenuContainer::new(16., 420., 520., 704.)],
+    selectables,
+    unselectables,
+    MenuMovement::Grid,
+    0,
+    0,
+    OnClickEvent::SetBattleMenu(main_battle_menu)
+  )
+}
+
+pub fn single_target_targeting_everyone(party: &[Character], enemies: &mut [Vec<Enemy>], ability: Ability) -> MenuScreen {
+  let mut selectables = Vec::new();
+  let targeting_start_type = ability.get_target_start();
+  push_party_to_selectables(&mut selectables, party, &ability);
+  push_enemies_to_selectables(&mut selectables, enemies, &ability, perform_on_targets);
   let (cursor_x_pos, cursor_y_pos) = match targeting_start_type {
     BattleActionTargetStart::Enemies => (0, 1),
     BattleActionTargetStart::Party => (0, 0),
-    BattleActionTargetStart::Myself => (get_character_in_turn!(party).unwrap().get_id(), 0)
+    BattleActionTargetStart::Myself => (party.iter().position(|character: &Character| character.get_battle_state().is_turn_active()).unwrap(), 0)
   };
   MenuScreen::new(
     vec![MenuContainer::new(16., 420., 250., 704.)],
@@ -48,64 +82,416 @@ pub fn single_target_targeting_everyone(
   )
 }
 
-fn push_party_to_selectables(selectables: &mut Vec<Vec<MenuItem>>, party: &Vec<Character>, action_effects: ActionTuple) {
-  let perform_battle_action = |
-    party: &mut Vec<Character>,
-    _enemies: &mut Vec<Vec<Enemy>>,
-    target_ids: Vec<usize>,
-    action_effects: ActionTuple,
+pub fn all_enemies_targeting(_party: &[Character], enemies: &mut [Vec<Enemy>], ability: Ability) -> MenuScreen {
+  let targets: Vec<(usize, (f32, f32))> = enemies.iter().flat_map(|enemy_row: &Vec<Enemy>| alive_enemy_targets(enemy_row)).collect();
+  group_targeting_menu(group_menuitem(targets, perform_on_targets, &ability).into_iter().collect())
+}
+
+pub fn all_allies_targeting(party: &[Character], _enemies: &mut [Vec<Enemy>], ability: Ability) -> MenuScreen {
+  group_targeting_menu(group_menuitem(alive_party_targets(party), perform_on_targets, &ability).into_iter().collect())
+}
+
+// enemy rows stand side by side, so left and right move between them
+pub fn enemy_row_targeting(_party: &[Character], enemies: &mut [Vec<Enemy>], ability: Ability) -> MenuScreen {
+  let rows = enemies.iter()
+    .map(|enemy_row: &Vec<Enemy>| alive_enemy_targets(enemy_row))
+    .filter_map(|targets: Vec<(usize, (f32, f32))>| group_menuitem(targets, perform_on_targets, &ability))
+    .collect();
+  group_targeting_menu(rows)
+}
+
+pub fn random_enemies_targeting(_party: &[Character], enemies: &mut [Vec<Enemy>], ability: Ability) -> MenuScreen {
+  let targets: Vec<(usize, (f32, f32))> = enemies.iter().flat_map(|enemy_row: &Vec<Enemy>| alive_enemy_targets(enemy_row)).collect();
+  group_targeting_menu(group_menuitem(targets, perform_random_hits, &ability).into_iter().collect())
+}
+
+pub fn myself_targeting(party: &[Character], _enemies: &mut [Vec<Enemy>], ability: Ability) -> MenuScreen {
+  let acting_character = get_character_in_turn!(party).unwrap();
+  let targets = vec![(acting_character.get_id(), acting_character.get_coords())];
+  group_targeting_menu(group_menuitem(targets, perform_on_targets, &ability).into_iter().collect())
+}
+
+pub fn steal_targeting(_party: &[Character], enemies: &mut [Vec<Enemy>], ability: Ability) -> MenuScreen {
+  let mut selectables = Vec::new();
+  push_enemies_to_selectables(&mut selectables, enemies, &ability, perform_steal);
+  MenuScreen::new(
+    vec![MenuContainer::new(16., 420., 250., 704.)],
+    selectables, Vec::new(),
+    MenuMovement::RowOfColumns,
+    0,
+    0,
+    OnClickEvent::SetBattleMenu(main_battle_menu)
+  )
+}
+
+pub fn escape_targeting(party: &[Character], enemies: &mut [Vec<Enemy>], ability: Ability) -> MenuScreen {
+  let escape = |
+    rng: &mut dyn Rng,
+    party: &mut [Character],
+    enemies: &mut [Vec<Enemy>],
+    _inventory: &mut Inventory,
+    _target_ids: Vec<usize>,
+    ability: Ability,
     notification: &mut Notification
   | {
     let acting_character = party.iter_mut().find(|character: &&mut Character| character.get_battle_state().is_turn_active()).unwrap();
-    acting_character.perform_battle_action();
+    ability.perform(acting_character.get_battle_state_mut(), notification, rng);
 
-    let incoming_damage = action_effects.0(acting_character.get_battle_state_mut(), notification);
-    for character in party.iter_mut() {
-      if target_ids.contains(&character.get_id()) {
-        character.receive_battle_action(action_effects.1, incoming_damage);
+    let party_agility = average_agility(party.iter()
+      .map(|character: &Character| character.get_battle_state())
+      .filter(|state: &&BattleState| state.get_hp() > 0));
+    let enemy_agility = average_agility(enemies.iter().flatten()
+      .map(|enemy: &Enemy| enemy.get_battle_state())
+      .filter(|state: &&BattleState| state.get_hp() > 0));
+    let escape_chance = (0.5 * party_agility / enemy_agility).clamp(0.1, 0.95);
+    if rng.random() < escape_chance {
+      notification.set_notification(String::from("Escaped!"));
+      for character in iter_alive_members!(party) {
+        character.flee();
       }
+    } else {
+      notification.set_notification(String::from("Couldn't escape!"));
+      let acting_character = party.iter_mut().find(|character: &&mut Character| character.get_battle_state().is_turn_active()).unwrap();
+      acting_character.perform_battle_action(Animation::EndTurn);
     }
   };
 
-  selectables.push(Vec::new());
+  let can_escape = enemies.iter().flatten().all(|enemy: &Enemy| enemy.is_escapable());
+  let acting_character = get_character_in_turn!(party).unwrap();
+  let (x, y) = acting_character.get_coords();
+  let target_ids = party.iter()
+    .filter(|character: &&Character| character.get_battle_state().get_hp() > 0)
+    .map(|character: &Character| character.get_id())
+    .collect();
+  let mut escape_menuitem = MenuItem::new(String::new(), x, y, OnClickEvent::BattleAction(escape, target_ids, ability));
+  escape_menuitem.set_enabled(can_escape);
+  let unselectables = if can_escape {
+    Vec::new()
+  } else {
+    vec![MenuItem::new(String::from("Can't escape"), 40., 468., OnClickEvent::None)]
+  };
+  MenuScreen::new(
+    vec![MenuContainer::new(16., 420., 250., 704.)],
+    vec![vec![escape_menuitem]],
+    unselectables,
+    MenuMovement::Grid,
+    0,
+    0,
+    OnClickEvent::SetBattleMenu(main_battle_menu)
+  )
+}
+
+fn average_agility<'a>(states: impl Iterator<Item = &'a BattleState>) -> f64 {
+  let (total, count) = states.fold((0., 0.), |(total, count): (f64, f64), state: &BattleState| (total + state.get_total_agility_stat(), count + 1.));
+  if count > 0. {
+    total / count
+  } else {
+    1.
+  }
+}
+
+pub fn item_menu(_party: &mut [Character], inventory: &Inventory) -> MenuScreen {
+  let mut selectables = Vec::new();
+  let mut unselectables = Vec::new();
+  for (i, (item, quantity)) in inventory.get_items().iter().enumerate() {
+    let y = 468. + i as f32 * 32.;
+    selectables.push(vec![MenuItem::new(item.get_name(), 70., y, OnClickEvent::ToItemTargetSelection(item_targeting_everyone, item.get_name()))]);
+    unselectables.push(MenuItem::new(format!("{:>2}", quantity), 440., y, OnClickEvent::None));
+  }
+  MenuScreen::new(
+    vec![MenuContainer::new(16., 420., 520., 704.)],
+    selectables,
+    unselectables,
+    MenuMovement::Grid,
+    0,
+    0,
+    OnClickEvent::SetBattleMenu(main_battle_menu)
+  )
+}
+
+pub fn item_targeting_everyone(party: &[Character], enemies: &mut [Vec<Enemy>], item_name: String) -> MenuScreen {
+  let mut selectables = vec![Vec::new()];
   for character in party.iter() {
     let (x, y) = character.get_coords();
-    selectables[0].push(MenuItem::new(String::new(), x, y, OnClickEvent::BattleAction(perform_battle_action, vec![character.get_id()], action_effects)));
+    selectables[0].push(MenuItem::new(String::new(), x, y, OnClickEvent::UseBattleItem(item_name.to_owned(), character.get_id())));
+  }
+  for enemy_row in enemies.iter() {
+    let targets: Vec<MenuItem> = enemy_row.iter()
+      .filter(|enemy: &&Enemy| enemy.get_battle_state().get_hp() > 0)
+      .map(|enemy: &Enemy| {
+        let (x, y) = enemy.get_target_coords();
+        MenuItem::new(String::new(), x, y, OnClickEvent::UseBattleItem(item_name.to_owned(), enemy.get_id()))
+      })
+      .collect();
+    if !targets.is_empty() {
+      selectables.push(targets);
+    }
   }
+  MenuScreen::new(
+    vec![MenuContainer::new(16., 420., 250., 704.)],
+    selectables, Vec::new(),
+    MenuMovement::RowOfColumns,
+    0,
+    0,
+    OnClickEvent::SetMenu(item_menu)
+  )
 }
 
-fn push_enemies_to_selectables(selectables: &mut Vec<Vec<MenuItem>>, enemies: &Vec<Vec<Enemy>>, action_effects: ActionTuple) {
-  let perform_battle_action = |
-    party: &mut Vec<Character>,
-    enemies: &mut Vec<Vec<Enemy>>,
-    target_ids: Vec<usize>,
-    action_effects: ActionTuple,
-    notification: &mut Notification
-  | {
-    let acting_character = party.iter_mut().find(|character: &&mut Character| character.get_battle_state().is_turn_active()).unwrap();
-    acting_character.perform_battle_action();
+pub fn get_targeting_menu(name: &str) -> Option<TargetingMenu> {
+  match name {
+    "single_target"  => Some(single_target_targeting_everyone),
+    "all_enemies"    => Some(all_enemies_targeting),
+    "all_allies"     => Some(all_allies_targeting),
+    "enemy_row"      => Some(enemy_row_targeting),
+    "random_enemies" => Some(random_enemies_targeting),
+    "myself"         => Some(myself_targeting),
+    "steal"          => Some(steal_targeting),
+    "escape"         => Some(escape_targeting),
+    _ => None
+  }
+}
 
-    for enemy in enemies.iter_mut().flatten() {
-      if target_ids.contains(&enemy.get_id()) {
-        enemy.receive_battle_action(action_effects.1, action_effects.0(acting_character.get_battle_state_mut(), notification));
-      }
-    }
-  };
+pub fn get_target_scope(targeting: &str) -> TargetScope {
+  match targeting {
+    "all_enemies" | "all_allies" => TargetScope::All,
+    "enemy_row"                  => TargetScope::Row,
+    "random_enemies"             => TargetScope::Random,
+    _ => TargetScope::Single
+  }
+}
 
-  for (i, enemy_row) in enemies.iter().enumerate() {
+fn push_party_to_selectables(selectables: &mut Vec<Vec<MenuItem>>, party: &[Character], ability: &Ability) {
+  selectables.push(Vec::new());
+  for character in party.iter() {
+    let (x, y) = character.get_coords();
+    selectables[0].push(MenuItem::new(String::new(), x, y, OnClickEvent::BattleAction(perform_on_targets, vec![character.get_id()], ability.clone())));
+  }
+}
+
+fn push_enemies_to_selectables(selectables: &mut Vec<Vec<MenuItem>>, enemies: &[Vec<Enemy>], ability: &Ability, action: BattleActionFunction) {
+  for enemy_row in enemies.iter() {
     selectables.push(Vec::new());
-    for (j, enemy) in enemy_row.iter().enumerate() {
+    for enemy in enemy_row.iter() {
       if enemy.get_battle_state().get_hp() > 0 {
+        let (x, y) = enemy.get_target_coords();
         selectables.last_mut().unwrap().push(MenuItem::new(
           String::new(),
-          700. + i as f32 * 100.,
-          180. + j as f32 * 100.,
-          OnClickEvent::BattleAction(perform_battle_action, vec![enemy.get_id()], action_effects)
+          x,
+          y,
+          OnClickEvent::BattleAction(action, vec![enemy.get_id()], ability.clone())
         ));
       }
     }
-    if selectables.last().unwrap().len() == 0 {
+    if selectables.last().unwrap().is_empty() {
       selectables.remove(selectables.len() - 1);
     }
   }
 }
+
+fn perform_on_targets(
+  rng: &mut dyn Rng,
+  party: &mut [Character],
+  enemies: &mut [Vec<Enemy>],
+  _inventory: &mut Inventory,
+  target_ids: Vec<usize>,
+  ability: Ability,
+  notification: &mut Notification
+) {
+  let acting_character = party.iter_mut().find(|character: &&mut Character| character.get_battle_state().is_turn_active()).unwrap();
+  acting_character.perform_battle_action(ability.get_animation());
+
+  let incoming_damage = ability.perform(acting_character.get_battle_state_mut(), notification, rng);
+  let damage_per_hit = ability.get_damage_per_hit(incoming_damage, target_ids.len());
+  // every hit on the same battler lands at once, a second hurt animation would cut the first one short
+  for character in party.iter_mut() {
+    let hits = target_ids.iter().filter(|id: &&usize| **id == character.get_id()).count();
+    if hits == 0 {
+      continue;
+    }
+    for _ in 0..hits {
+      if let Some(status_change) = ability.get_status_change() {
+        character.get_battle_state_mut().apply_status_change(status_change, rng);
+      }
+    }
+    if let Some(target_action) = ability.get_target_action() {
+      character.receive_battle_action(target_action, damage_per_hit * hits as f64, ability.get_element());
+    }
+  }
+  for enemy in enemies.iter_mut().flatten() {
+    let hits = target_ids.iter().filter(|id: &&usize| **id == enemy.get_id()).count();
+    if hits == 0 {
+      continue;
+    }
+    for _ in 0..hits {
+      if let Some(status_change) = ability.get_status_change() {
+        enemy.get_battle_state_mut().apply_status_change(status_change, rng);
+      }
+    }
+    if let Some(target_action) = ability.get_target_action() {
+      enemy.receive_battle_action(target_action, damage_per_hit * hits as f64, ability.get_element());
+    }
+  }
+}
+
+fn perform_random_hits(
+  rng: &mut dyn Rng,
+  party: &mut [Character],
+  enemies: &mut [Vec<Enemy>],
+  inventory: &mut Inventory,
+  target_ids: Vec<usize>,
+  ability: Ability,
+  notification: &mut Notification
+) {
+  let hit_ids = (0..ability.get_hits())
+    .map(|_| target_ids[(rng.random() * target_ids.len() as f64).floor() as usize])
+    .collect();
+  perform_on_targets(rng, party, enemies, inventory, hit_ids, ability, notification);
+}
+
+// quicker and higher level thieves get away with it more often
+fn perform_steal(
+  rng: &mut dyn Rng,
+  party: &mut [Character],
+  enemies: &mut [Vec<Enemy>],
+  inventory: &mut Inventory,
+  target_ids: Vec<usize>,
+  ability: Ability,
+  notification: &mut Notification
+) {
+  let acting_character = party.iter_mut().find(|character: &&mut Character| character.get_battle_state().is_turn_active()).unwrap();
+  acting_character.perform_battle_action(ability.get_animation());
+  ability.perform(acting_character.get_battle_state_mut(), notification, rng);
+
+  let thief_state = acting_character.get_battle_state();
+  let target = match enemies.iter_mut().flatten().find(|enemy: &&mut Enemy| target_ids.contains(&enemy.get_id())) {
+    Some(enemy) => enemy,
+    None => return
+  };
+  if !target.has_anything_to_steal() {
+    notification.set_notification(String::from("Nothing to steal"));
+    return;
+  }
+  let target_state = target.get_battle_state();
+  let level_difference = thief_state.get_level() as f64 - target_state.get_level() as f64;
+  let agility_difference = thief_state.get_total_agility_stat() - target_state.get_total_agility_stat();
+  let steal_chance = (0.5 + level_difference * 0.05 + agility_difference * 0.02).clamp(0.05, 0.95);
+  if rng.random() >= steal_chance {
+    notification.set_notification(String::from("Couldn't steal"));
+    return;
+  }
+  match target.steal(rng) {
+    Some(item) => {
+      notification.set_notification(format!("Stole {}", item.get_name()));
+      inventory.add_item(item, 1);
+    },
+    None => notification.set_notification(String::from("Couldn't steal"))
+  }
+}
+
+// one menu item standing for a whole group, with the cursor on every member of it, None when nobody is left in the group
+fn group_menuitem(targets: Vec<(usize, (f32, f32))>, action: BattleActionFunction, ability: &Ability) -> Option<MenuItem> {
+  let (x, y) = targets.first()?.1;
+  let target_ids = targets.iter().map(|(id, _): &(usize, (f32, f32))| *id).collect();
+  let mut menu_item = MenuItem::new(String::new(), x, y, OnClickEvent::BattleAction(action, target_ids, ability.clone()));
+  menu_item.set_highlights(targets.iter().map(|(_, coords): &(usize, (f32, f32))| *coords).collect());
+  Some(menu_item)
+}
+
+fn alive_party_targets(party: &[Character]) -> Vec<(usize, (f32, f32))> {
+  party.iter()
+    .filter(|character: &&Character| character.get_battle_state().get_hp() > 0)
+    .map(|character: &Character| (character.get_id(), character.get_coords()))
+    .collect()
+}
+
+fn alive_enemy_targets(enemy_row: &[Enemy]) -> Vec<(usize, (f32, f32))> {
+  enemy_row.iter()
+    .filter(|enemy: &&Enemy| enemy.get_battle_state().get_hp() > 0)
+    .map(|enemy: &Enemy| (enemy.get_id(), enemy.get_target_coords()))
+    .collect()
+}
+
+// the groups sit side by side on one row, with nothing to pick the menu can only be backed out of
+fn group_targeting_menu(groups: Vec<MenuItem>) -> MenuScreen {
+  let selectables = if groups.is_empty() { Vec::new() } else { vec![groups] };
+  MenuScreen::new(
+    vec![MenuContainer::new(16., 420., 250., 704.)],
+    selectables, Vec::new(),
+    MenuMovement::Grid,
+    0,
+    0,
+    OnClickEvent::SetBattleMenu(main_battle_menu)
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::backend::headless::{DrawCall, RecordingRenderer};
+  use crate::game::battle::ability::{AbilityAnimation, AbilityParams, DamageSpread};
+  use crate::game::data::content::Content;
+
+  fn cursor_count(menu: &MenuScreen) -> usize {
+    let mut renderer = RecordingRenderer::new();
+    menu.draw(&mut renderer);
+    renderer.take_frame().iter().filter(|call: &&DrawCall| call.key == "cursor").count()
+  }
+
+  #[test]
+  fn empty_groups_leave_nothing_to_target() {
+    let content = Content::default_content();
+    let mut party = content.create_starting_party();
+    let cleave = party[0].get_skills().iter().find(|skill: &&Ability| skill.get_name() == "Cleave").unwrap().clone();
+    for character in party.iter_mut() {
+      character.get_battle_state_mut().reduce_hp(u16::MAX);
+    }
+    let mut no_enemies: Vec<Vec<Enemy>> = vec![Vec::new(), Vec::new()];
+    assert_eq!(cursor_count(&all_allies_targeting(&party, &mut no_enemies, cleave.clone())), 0);
+    assert_eq!(cursor_count(&all_enemies_targeting(&party, &mut no_enemies, cleave.clone())), 0);
+    assert_eq!(cursor_count(&enemy_row_targeting(&party, &mut no_enemies, cleave.clone())), 0);
+    assert_eq!(cursor_count(&random_enemies_targeting(&party, &mut no_enemies, cleave)), 0);
+  }
+
+  #[test]
+  fn enemy_rows_skip_the_emptied_ones() {
+    let content = Content::default_content();
+    let party = content.create_starting_party();
+    let cleave = party[0].get_skills().iter().find(|skill: &&Ability| skill.get_name() == "Cleave").unwrap().clone();
+    let mut enemies = content.create_formation("test_room_formation_2").unwrap();
+    for enemy in enemies[0].iter_mut() {
+      enemy.get_battle_state_mut().reduce_hp(u16::MAX);
+    }
+    // the cursor lands on the one enemy left in the back row
+    assert_eq!(cursor_count(&enemy_row_targeting(&party, &mut enemies, cleave)), 1);
+  }
+
+  #[test]
+  fn single_target_starting_on_myself_points_at_the_character_in_turn() {
+    let content = Content::default_content();
+    let mut party = content.create_starting_party();
+    for character in party.iter_mut() {
+      character.start_battle(false);
+    }
+    party[3].start_turn();
+    let on_myself = Ability::new(String::from("Focus"), AbilityParams {
+      mp_cost: 0,
+      power: 1.,
+      targeting: single_target_targeting_everyone,
+      scope: TargetScope::Single,
+      action: None,
+      status_change: None,
+      element: None,
+      target_start: BattleActionTargetStart::Myself,
+      animation: AbilityAnimation::Cast,
+      hits: 1,
+      spread: DamageSpread::Full
+    });
+    let menu = single_target_targeting_everyone(&party, &mut Vec::new(), on_myself);
+    let mut renderer = RecordingRenderer::new();
+    menu.draw(&mut renderer);
+    let cursor = renderer.take_frame().into_iter().find(|call: &DrawCall| call.key == "cursor").unwrap();
+    let mut expected = RecordingRenderer::new();
+    MenuScreen::new(Vec::new(), vec![vec![MenuItem::new(String::new(), party[3].get_coords().0, party[3].get_coords().1, OnClickEvent::None)]], Vec::new(), MenuMovement::Grid, 0, 0, OnClickEvent::None).draw(&mut expected);
+    assert!(expected.take_frame().contains(&cursor));
+  }
+}
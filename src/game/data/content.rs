@@ -0,0 +1,632 @@
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
+use std::collections::HashMap;
+use crate::game::battle::{BattleActionTargetStart, BattleRow};
+use crate::game::battle::ability::{Ability, AbilityAnimation, AbilityParams, DamageSpread};
+use crate::game::battle::ai::{AiCondition, AiRule, AiTarget};
+use crate::game::battle::character::{Character, CharacterAbilities};
+use crate::game::battle::element::{Affinity, Element};
+use crate::game::battle::enemy::{Enemy, EnemyRewards, StealTable};
+use crate::game::battle::state::{BaseStats, GrowthRates};
+use crate::game::battle::status::{StatusChange, StatusEffect};
+use crate::game::data::{battle_actions, battle_menus, maps};
+use crate::game::inventory::Inventory;
+use crate::game::inventory::equipment::{Equipment, EquipmentSlot, StatBonuses};
+use crate::game::inventory::item::{Item, ItemEffect};
+
+const DEFAULT_CONTENT: &str = include_str!("../../../resources/data/content.json");
+
+pub const MAX_PARTY_SIZE: usize = 4;
+// Party members use ids 1-4, so enemy ids start right after them.
+const FIRST_ENEMY_ID: usize = MAX_PARTY_SIZE + 1;
+// where formation entries without explicit coordinates are laid out
+const FORMATION_X: f32 = 700.;
+const FORMATION_Y: f32 = 180.;
+const FORMATION_SPACING: f32 = 100.;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AbilityDefinition {
+  #[serde(default)]
+  action: Option<String>,
+  #[serde(default)]
+  status: Option<StatusChange>,
+  #[serde(default)]
+  element: Option<Element>,
+  targeting: String,
+  target_start: BattleActionTargetStart,
+  #[serde(default)]
+  mp_cost: u16,
+  #[serde(default = "default_power")]
+  power: f64,
+  #[serde(default)]
+  animation: AbilityAnimation,
+  #[serde(default = "default_hits")]
+  hits: u32,
+  #[serde(default)]
+  spread: DamageSpread
+}
+
+fn default_power() -> f64 {
+  1.
+}
+
+fn default_hits() -> u32 {
+  1
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CharacterAbilitiesDefinition {
+  attack: String,
+  primary: String,
+  secondary: String,
+  #[serde(default)]
+  skills: Vec<String>
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CharacterDefinition {
+  sprite_folder: String,
+  level: u32,
+  stats: BaseStats,
+  growth_rates: GrowthRates,
+  abilities: CharacterAbilitiesDefinition,
+  #[serde(default)]
+  equipment: Vec<String>,
+  #[serde(default)]
+  row: BattleRow,
+  // story critical members can't be benched or moved around the party
+  #[serde(default)]
+  locked: bool
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AiRuleDefinition {
+  ability: String,
+  target: AiTarget,
+  #[serde(default)]
+  condition: AiCondition,
+  #[serde(default)]
+  priority: u32,
+  #[serde(default = "default_weight")]
+  weight: u32
+}
+
+fn default_weight() -> u32 {
+  1
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DropDefinition {
+  item: String,
+  chance: f64
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StealDefinition {
+  #[serde(default)]
+  common: Option<String>,
+  #[serde(default)]
+  rare: Option<String>
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EnemyDefinition {
+  sprite: String,
+  experience: u32,
+  #[serde(default)]
+  gold: u32,
+  #[serde(default)]
+  drops: Vec<DropDefinition>,
+  #[serde(default)]
+  steal: StealDefinition,
+  level: u32,
+  stats: BaseStats,
+  #[serde(default = "default_sprite_size")]
+  width: f32,
+  #[serde(default = "default_sprite_size")]
+  height: f32,
+  ai: Vec<AiRuleDefinition>,
+  #[serde(default)]
+  immunities: Vec<StatusEffect>,
+  #[serde(default)]
+  affinities: HashMap<Element, Affinity>
+}
+
+fn default_sprite_size() -> f32 {
+  64.
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PlacedEnemy {
+  name: String,
+  x: f32,
+  y: f32,
+  #[serde(default)]
+  width: Option<f32>,
+  #[serde(default)]
+  height: Option<f32>
+}
+
+// formation entries are either a plain enemy name or a name with a placement
+enum FormationEnemy {
+  Name(String),
+  Placed(PlacedEnemy)
+}
+
+// picked by hand so a bad placement reports what is wrong with it, not just that nothing matched
+impl<'de> Deserialize<'de> for FormationEnemy {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    match serde_json::Value::deserialize(deserializer)? {
+      serde_json::Value::String(name) => Ok(FormationEnemy::Name(name)),
+      placed @ serde_json::Value::Object(_) => serde_json::from_value(placed)
+        .map(FormationEnemy::Placed)
+        .map_err(|error: serde_json::Error| D::Error::custom(format!("invalid placed formation enemy, {}", error))),
+      _ => Err(D::Error::custom("formation enemies must be an enemy name or an object with a name, x and y"))
+    }
+  }
+}
+
+impl FormationEnemy {
+  fn get_name(&self) -> &String {
+    match self {
+      FormationEnemy::Name(name) => name,
+      FormationEnemy::Placed(placed) => &placed.name
+    }
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FormationDefinition {
+  enemies: Vec<Vec<FormationEnemy>>,
+  #[serde(default = "default_can_flee")]
+  can_flee: bool
+}
+
+fn default_can_flee() -> bool {
+  true
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ItemDefinition {
+  effect: ItemEffect
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EquipmentDefinition {
+  slot: EquipmentSlot,
+  #[serde(default)]
+  bonuses: StatBonuses,
+  #[serde(default)]
+  affinities: HashMap<Element, Affinity>
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Content {
+  abilities: HashMap<String, AbilityDefinition>,
+  characters: HashMap<String, CharacterDefinition>,
+  enemies: HashMap<String, EnemyDefinition>,
+  formations: HashMap<String, FormationDefinition>,
+  #[serde(default)]
+  items: HashMap<String, ItemDefinition>,
+  #[serde(default)]
+  equipment: HashMap<String, EquipmentDefinition>,
+  starting_party: Vec<String>,
+  #[serde(default)]
+  starting_reserves: Vec<String>,
+  #[serde(default)]
+  starting_inventory: Vec<(String, u32)>,
+  #[serde(default)]
+  starting_gold: u32
+}
+
+impl Content {
+  pub fn default_content() -> Self {
+    Content::from_json(DEFAULT_CONTENT).unwrap()
+  }
+
+  pub fn from_json(json: &str) -> Result<Self, String> {
+    let content: Content = serde_json::from_str(json).map_err(|error| format!("Invalid content data: {}", error))?;
+    content.validate()?;
+    maps::validate_encounters(&content)?;
+    Ok(content)
+  }
+
+  fn validate(&self) -> Result<(), String> {
+    for (name, ability) in self.abilities.iter() {
+      match &ability.action {
+        Some(action) if battle_actions::get_action(action).is_none() => {
+          return Err(format!("Ability '{}' uses unknown action '{}'", name, action));
+        },
+        None if ability.status.is_none() && ability.targeting != "escape" && ability.targeting != "steal" => {
+          return Err(format!("Ability '{}' has neither an action nor a status", name));
+        },
+        _ => ()
+      }
+      if battle_menus::get_targeting_menu(&ability.targeting).is_none() {
+        return Err(format!("Ability '{}' uses unknown targeting '{}'", name, ability.targeting));
+      }
+      if ability.power <= 0. {
+        return Err(format!("Ability '{}' must have positive power", name));
+      }
+      if ability.hits == 0 {
+        return Err(format!("Ability '{}' must hit at least once", name));
+      }
+    }
+    for (name, character) in self.characters.iter() {
+      validate_stats(&format!("Character '{}'", name), character.level, &character.stats)?;
+      let command_abilities = [&character.abilities.attack, &character.abilities.primary, &character.abilities.secondary];
+      for ability_name in command_abilities.iter().copied().chain(character.abilities.skills.iter()) {
+        if !self.abilities.contains_key(ability_name) {
+          return Err(format!("Character '{}' has unknown ability '{}'", name, ability_name));
+        }
+      }
+      let mut used_slots = Vec::new();
+      for equipment_name in character.equipment.iter() {
+        let equipment = self.equipment.get(equipment_name)
+          .ok_or_else(|| format!("Character '{}' has unknown equipment '{}'", name, equipment_name))?;
+        if used_slots.contains(&equipment.slot) {
+          return Err(format!("Character '{}' has more than one {} equipped", name, equipment.slot.get_name()));
+        }
+        used_slots.push(equipment.slot);
+      }
+    }
+    for (name, enemy) in self.enemies.iter() {
+      validate_stats(&format!("Enemy '{}'", name), enemy.level, &enemy.stats)?;
+      if enemy.width <= 0. || enemy.height <= 0. {
+        return Err(format!("Enemy '{}' must have a positive width and height", name));
+      }
+      for drop in enemy.drops.iter() {
+        if !self.is_inventory_entry(&drop.item) {
+          return Err(format!("Enemy '{}' drops unknown item '{}'", name, drop.item));
+        }
+        if drop.chance <= 0. || drop.chance > 1. {
+          return Err(format!("Enemy '{}' must drop '{}' with a chance between 0 and 1", name, drop.item));
+        }
+      }
+      // only consumables can be stolen, the steal table holds ready made items
+      for item_name in enemy.steal.common.iter().chain(enemy.steal.rare.iter()) {
+        if !self.items.contains_key(item_name) {
+          return Err(format!("Enemy '{}' can have unknown item '{}' stolen", name, item_name));
+        }
+      }
+      if enemy.ai.is_empty() {
+        return Err(format!("Enemy '{}' has no ai rules", name));
+      }
+      for rule in enemy.ai.iter() {
+        if !self.abilities.contains_key(&rule.ability) {
+          return Err(format!("Enemy '{}' has an ai rule with unknown ability '{}'", name, rule.ability));
+        }
+        if rule.weight == 0 {
+          return Err(format!("Enemy '{}' has an ai rule for '{}' with 0 weight", name, rule.ability));
+        }
+        if let AiCondition::EveryNthTurn { turns: 0 } = rule.condition {
+          return Err(format!("Enemy '{}' has an ai rule for '{}' every 0 turns", name, rule.ability));
+        }
+      }
+    }
+    for (name, formation) in self.formations.iter() {
+      if formation.enemies.iter().flatten().count() == 0 {
+        return Err(format!("Formation '{}' has no enemies", name));
+      }
+      for formation_enemy in formation.enemies.iter().flatten() {
+        if !self.enemies.contains_key(formation_enemy.get_name()) {
+          return Err(format!("Formation '{}' has unknown enemy '{}'", name, formation_enemy.get_name()));
+        }
+        if let FormationEnemy::Placed(placed) = formation_enemy {
+          if placed.width.is_some_and(|width: f32| width <= 0.) || placed.height.is_some_and(|height: f32| height <= 0.) {
+            return Err(format!("Formation '{}' must give '{}' a positive width and height", name, placed.name));
+          }
+        }
+      }
+    }
+    if self.starting_party.is_empty() || self.starting_party.len() > MAX_PARTY_SIZE {
+      return Err(format!("Starting party must have between 1 and {} members", MAX_PARTY_SIZE));
+    }
+    for character_name in self.starting_party.iter() {
+      if !self.characters.contains_key(character_name) {
+        return Err(format!("Starting party has unknown character '{}'", character_name));
+      }
+    }
+    for character_name in self.starting_reserves.iter() {
+      if !self.characters.contains_key(character_name) {
+        return Err(format!("Starting reserves have unknown character '{}'", character_name));
+      }
+    }
+    for (name, item) in self.items.iter() {
+      match &item.effect {
+        ItemEffect::RestoreHp { amount } | ItemEffect::RestoreMp { amount } if *amount == 0 => {
+          return Err(format!("Item '{}' must restore more than 0", name));
+        },
+        ItemEffect::CureStatus { statuses } if statuses.is_empty() => {
+          return Err(format!("Item '{}' must cure at least one status", name));
+        },
+        _ => ()
+      }
+    }
+    if let Some(name) = self.items.keys().find(|name: &&String| self.equipment.contains_key(*name)) {
+      return Err(format!("'{}' is defined as both an item and equipment", name));
+    }
+    for (item_name, quantity) in self.starting_inventory.iter() {
+      if !self.is_inventory_entry(item_name) {
+        return Err(format!("Starting inventory has unknown item '{}'", item_name));
+      }
+      if *quantity == 0 {
+        return Err(format!("Starting inventory must have at least 1 '{}'", item_name));
+      }
+    }
+    Ok(())
+  }
+
+  pub fn create_character(&self, name: &str, id: usize) -> Option<Character> {
+    let character = self.characters.get(name)?;
+    let mut new_character = Character::new(
+      name.to_string(),
+      character.sprite_folder.to_owned(),
+      id,
+      character.level,
+      character.stats,
+      character.growth_rates,
+      CharacterAbilities {
+        attack: self.create_ability(&character.abilities.attack),
+        primary: self.create_ability(&character.abilities.primary),
+        secondary: self.create_ability(&character.abilities.secondary),
+        skills: character.abilities.skills.iter().map(|skill: &String| self.create_ability(skill)).collect()
+      }
+    );
+    for equipment_name in character.equipment.iter() {
+      new_character.equip(self.create_equipment(equipment_name).unwrap());
+    }
+    new_character.get_battle_state_mut().set_row(character.row);
+    new_character.set_locked(character.locked);
+    Some(new_character)
+  }
+
+  pub fn create_starting_party(&self) -> Vec<Character> {
+    self.starting_party.iter().enumerate().map(|(index, name): (usize, &String)| {
+      self.create_character(name, index + 1).unwrap()
+    }).collect()
+  }
+
+  // reserves sit outside the battle slots until they join the party
+  pub fn create_starting_reserves(&self) -> Vec<Character> {
+    self.starting_reserves.iter().map(|name: &String| self.create_character(name, 0).unwrap()).collect()
+  }
+
+  pub fn create_enemy(&self, name: &str, id: usize) -> Option<Enemy> {
+    let enemy = self.enemies.get(name)?;
+    let mut new_enemy = Enemy::new(
+      enemy.sprite.to_owned(),
+      name.to_string(),
+      id,
+      enemy.level,
+      enemy.stats,
+      EnemyRewards {
+        experience: enemy.experience,
+        gold: enemy.gold,
+        drops: enemy.drops.iter().map(|drop: &DropDefinition| (drop.item.to_owned(), drop.chance)).collect(),
+        steal_table: StealTable {
+          common: enemy.steal.common.as_ref().and_then(|item_name: &String| self.create_item(item_name)),
+          rare: enemy.steal.rare.as_ref().and_then(|item_name: &String| self.create_item(item_name))
+        }
+      },
+      enemy.ai.iter().map(|rule: &AiRuleDefinition| AiRule::new(
+        self.create_ability(&rule.ability),
+        rule.target,
+        rule.condition,
+        rule.priority,
+        rule.weight
+      )).collect()
+    );
+    new_enemy.get_battle_state_mut().set_immunities(enemy.immunities.clone());
+    new_enemy.get_battle_state_mut().set_affinities(to_affinity_list(&enemy.affinities));
+    Some(new_enemy)
+  }
+
+  pub fn create_item(&self, name: &str) -> Option<Item> {
+    let item = self.items.get(name)?;
+    Some(Item::new(name.to_string(), item.effect.clone()))
+  }
+
+  pub fn create_equipment(&self, name: &str) -> Option<Equipment> {
+    let equipment = self.equipment.get(name)?;
+    Some(Equipment::new(name.to_string(), equipment.slot, equipment.bonuses, to_affinity_list(&equipment.affinities)))
+  }
+
+  pub fn is_inventory_entry(&self, name: &str) -> bool {
+    self.items.contains_key(name) || self.equipment.contains_key(name)
+  }
+
+  pub fn add_to_inventory(&self, inventory: &mut Inventory, name: &str, quantity: u32) {
+    if let Some(item) = self.create_item(name) {
+      inventory.add_item(item, quantity);
+    } else if let Some(equipment) = self.create_equipment(name) {
+      inventory.add_equipment(equipment, quantity);
+    }
+  }
+
+  pub fn create_starting_inventory(&self) -> Inventory {
+    let mut inventory = Inventory::new();
+    for (name, quantity) in self.starting_inventory.iter() {
+      self.add_to_inventory(&mut inventory, name, *quantity);
+    }
+    inventory.add_gold(self.starting_gold);
+    inventory.set_reserves(self.create_starting_reserves());
+    inventory
+  }
+
+  pub fn has_formation(&self, name: &str) -> bool {
+    self.formations.contains_key(name)
+  }
+
+  // all enemy formations are row of columns
+  pub fn create_formation(&self, name: &str) -> Option<Vec<Vec<Enemy>>> {
+    let formation = self.formations.get(name)?;
+    let mut id = FIRST_ENEMY_ID;
+    let mut enemy_rows = Vec::new();
+    for (i, row) in formation.enemies.iter().enumerate() {
+      let mut enemy_row = Vec::new();
+      for (j, formation_enemy) in row.iter().enumerate() {
+        let definition = self.enemies.get(formation_enemy.get_name())?;
+        let mut enemy = self.create_enemy(formation_enemy.get_name(), id)?;
+        match formation_enemy {
+          FormationEnemy::Name(_) => enemy.set_placement(
+            FORMATION_X + i as f32 * FORMATION_SPACING,
+            FORMATION_Y + j as f32 * FORMATION_SPACING,
+            definition.width,
+            definition.height
+          ),
+          FormationEnemy::Placed(placed) => enemy.set_placement(
+            placed.x,
+            placed.y,
+            placed.width.unwrap_or(definition.width),
+            placed.height.unwrap_or(definition.height)
+          )
+        }
+        enemy.set_escapable(formation.can_flee);
+        enemy_row.push(enemy);
+        id += 1;
+      }
+      enemy_rows.push(enemy_row);
+    }
+    Some(enemy_rows)
+  }
+
+  fn create_ability(&self, name: &str) -> Ability {
+    let ability = self.abilities.get(name).unwrap();
+    Ability::new(name.to_string(), AbilityParams {
+      mp_cost: ability.mp_cost,
+      power: ability.power,
+      targeting: battle_menus::get_targeting_menu(&ability.targeting).unwrap(),
+      scope: battle_menus::get_target_scope(&ability.targeting),
+      action: ability.action.as_ref().map(|action: &String| battle_actions::get_action(action).unwrap()),
+      status_change: ability.status.clone(),
+      element: ability.element,
+      target_start: ability.target_start,
+      animation: ability.animation,
+      hits: ability.hits,
+      spread: ability.spread
+    })
+  }
+}
+
+fn to_affinity_list(affinities: &HashMap<Element, Affinity>) -> Vec<(Element, Affinity)> {
+  affinities.iter().map(|(element, affinity): (&Element, &Affinity)| (*element, *affinity)).collect()
+}
+
+fn validate_stats(owner: &str, level: u32, stats: &BaseStats) -> Result<(), String> {
+  if level == 0 {
+    return Err(format!("{} must be at least level 1", owner));
+  }
+  if stats.hp == 0 {
+    return Err(format!("{} must have more than 0 hp", owner));
+  }
+  if stats.def <= 0. || stats.res <= 0. {
+    return Err(format!("{} must have positive defence and resistance", owner));
+  }
+  if stats.agi <= 0. {
+    return Err(format!("{} must have positive agility", owner));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn edited_content(edit: fn(&mut serde_json::Value)) -> Result<Content, String> {
+    let mut json: serde_json::Value = serde_json::from_str(DEFAULT_CONTENT).unwrap();
+    edit(&mut json);
+    Content::from_json(&json.to_string())
+  }
+
+  fn validation_error(edit: fn(&mut serde_json::Value)) -> String {
+    match edited_content(edit) {
+      Ok(_) => panic!("edited content passed validation"),
+      Err(error) => error
+    }
+  }
+
+  #[test]
+  fn default_content_is_valid() {
+    assert!(Content::from_json(DEFAULT_CONTENT).is_ok());
+  }
+
+  #[test]
+  fn malformed_json_is_rejected() {
+    assert!(Content::from_json("{\"abilities\": ").err().unwrap().starts_with("Invalid content data"));
+  }
+
+  #[test]
+  fn unknown_ability_action_is_rejected() {
+    let error = validation_error(|json: &mut serde_json::Value| json["abilities"]["Attack"]["action"] = serde_json::json!("explode"));
+    assert_eq!(error, "Ability 'Attack' uses unknown action 'explode'");
+  }
+
+  #[test]
+  fn character_with_unknown_ability_is_rejected() {
+    let error = validation_error(|json: &mut serde_json::Value| json["characters"]["Darrel"]["abilities"]["primary"] = serde_json::json!("Meteor"));
+    assert_eq!(error, "Character 'Darrel' has unknown ability 'Meteor'");
+  }
+
+  #[test]
+  fn enemy_without_hp_is_rejected() {
+    let error = validation_error(|json: &mut serde_json::Value| json["enemies"]["Circle"]["stats"]["hp"] = serde_json::json!(0));
+    assert_eq!(error, "Enemy 'Circle' must have more than 0 hp");
+  }
+
+  #[test]
+  fn formation_with_unknown_enemy_is_rejected() {
+    let error = validation_error(|json: &mut serde_json::Value| json["formations"]["test_room_formation_1"]["enemies"] = serde_json::json!([["Hexagon"]]));
+    assert_eq!(error, "Formation 'test_room_formation_1' has unknown enemy 'Hexagon'");
+  }
+
+  #[test]
+  fn starting_party_size_is_checked() {
+    let error = validation_error(|json: &mut serde_json::Value| json["starting_party"] = serde_json::json!([]));
+    assert_eq!(error, "Starting party must have between 1 and 4 members");
+    let error = validation_error(|json: &mut serde_json::Value| json["starting_party"] = serde_json::json!(["Darrel", "Darrel", "Darrel", "Darrel", "Darrel"]));
+    assert_eq!(error, "Starting party must have between 1 and 4 members");
+  }
+
+  #[test]
+  fn starting_party_with_unknown_character_is_rejected() {
+    let error = validation_error(|json: &mut serde_json::Value| json["starting_party"] = serde_json::json!(["Darrel", "Nobody"]));
+    assert_eq!(error, "Starting party has unknown character 'Nobody'");
+  }
+
+  #[test]
+  fn map_encounter_with_unknown_formation_is_rejected() {
+    let error = validation_error(|json: &mut serde_json::Value| {
+      json["formations"].as_object_mut().unwrap().remove("test_room_formation_2");
+    });
+    assert_eq!(error, "Map 'debug_room' has encounters with unknown formation 'test_room_formation_2'");
+  }
+
+  #[test]
+  fn level_zero_is_rejected() {
+    let error = validation_error(|json: &mut serde_json::Value| json["characters"]["Darrel"]["level"] = serde_json::json!(0));
+    assert_eq!(error, "Character 'Darrel' must be at least level 1");
+    let error = validation_error(|json: &mut serde_json::Value| json["enemies"]["Square"]["level"] = serde_json::json!(0));
+    assert_eq!(error, "Enemy 'Square' must be at least level 1");
+  }
+
+  #[test]
+  fn malformed_formation_enemy_says_what_is_wrong() {
+    let error = validation_error(|json: &mut serde_json::Value| json["formations"]["test_room_formation_1"]["enemies"] = serde_json::json!([[{"name": "Circle", "x": 10}]]));
+    assert!(error.contains("invalid placed formation enemy, missing field `y`"));
+    let error = validation_error(|json: &mut serde_json::Value| json["formations"]["test_room_formation_1"]["enemies"] = serde_json::json!([[5]]));
+    assert!(error.contains("formation enemies must be an enemy name or an object with a name, x and y"));
+  }
+}
@@ -1,42 +1,44 @@
 use crate::game::animation::Direction;
-use crate::game::battle::enemy::Enemy;
-use crate::game::data::enemy_formations::*;
-use crate::game::data::npcs;
+use crate::game::data::content::Content;
 use crate::game::map::Map;
+use crate::game::map::encounter::EncounterTable;
 use crate::game::map::player::Player;
 
+// every name get_map knows, so content can be checked against all of them
+const MAP_NAMES: [&str; 2] = ["", "debug_room"];
+
 pub fn none_map(player: &mut Player) -> Map {
   player.set(0, 0, Direction::Down);
   Map::new(
+    String::new(),
     String::new(),
     String::new(),
     Vec::new(),
     Vec::new(),
-    |_rng_value: f64| -> Option<Vec<Vec<Enemy>>> {None}
+    EncounterTable::none()
   )
 }
 
 pub fn debug_room(player: &mut Player) -> Map {
-  player.set(3, 3, Direction::Down);
-  Map::new(
-    String::from("test_map"),
-    String::from("test_room_map"),
-    vec![
-      vec![("up_left_border",   true), ("up_border",      true ), ("up_border",   true ), ("up_border",      true ), ("up_border",   true ), ("up_border",      true ), ("up_right_border",   true)],
-      vec![("left_border",      true), ("floor_decour_1", false), ("floor",       false), ("floor",          false), ("floor",       false), ("floor_decour_2", false), ("right_border",      true)],
-      vec![("left_border",      true), ("floor",          false), ("floor",       false), ("floor",          false), ("floor",       false), ("floor",          false), ("right_border",      true)],
-      vec![("left_border",      true), ("floor_decour_2", false), ("floor",       false), ("floor",          false), ("floor",       false), ("floor",          false), ("right_border",      true)],
-      vec![("left_border",      true), ("floor",          false), ("floor",       false), ("floor_decour_1", false), ("floor",       false), ("floor",          false), ("right_border",      true)],
-      vec![("left_border",      true), ("floor_decour_1", false), ("floor",       false), ("floor",          false), ("floor",       false), ("floor_decour_1", false), ("right_border",      true)],
-      vec![("left_border",      true), ("floor_decour_2", false), ("floor",       false), ("floor",          false), ("floor",       false), ("floor_decour_1", false), ("right_border",      true)],
-      vec![("down_left_border", true), ("down_border",    true ), ("down_border", true ), ("down_border",    true ), ("down_border", true ), ("down_border",    true ), ("down_right_border", true)],
-    ],
-    vec![npcs::nurse_seraphine()],
-    |rng_value: f64| -> Option<Vec<Vec<Enemy>>> {
-      if rng_value > 0.1 {
-        return Some(test_room_formation_1());
+  Map::from_tiled(String::from("debug_room"), include_str!("../../../resources/tilesets/test_map/debug_room.json"), player).unwrap()
+}
+
+pub fn get_map(name: &str) -> Option<for<'a> fn(&'a mut Player) -> Map> {
+  match name {
+    ""           => Some(none_map),
+    "debug_room" => Some(debug_room),
+    _ => None
+  }
+}
+
+pub fn validate_encounters(content: &Content) -> Result<(), String> {
+  for map_name in MAP_NAMES.iter() {
+    let map = get_map(map_name).unwrap()(&mut Player::new());
+    for formation_name in map.get_encounter_formations() {
+      if !content.has_formation(formation_name) {
+        return Err(format!("Map '{}' has encounters with unknown formation '{}'", map_name, formation_name));
       }
-      None
     }
-  )
+  }
+  Ok(())
 }
@@ -1,62 +1,285 @@
+use crate::game::backend::{AudioPlayer, Rng};
+use crate::game::battle::BattleSpoils;
 use crate::game::battle::character::Character;
+use crate::game::battle::enemy::Enemy;
+use crate::game::battle::state::BattleState;
+use crate::game::battle::status::StatusEffect;
+use crate::game::config::Config;
+use crate::game::data::content::MAX_PARTY_SIZE;
 use crate::game::data::maps;
+use crate::game::inventory::{Inventory, MAX_GOLD};
+use crate::game::inventory::equipment::{Equipment, EquipmentSlot, StatBonuses};
 use crate::game::menu::container::MenuContainer;
-use crate::game::menu::click_event::OnClickEvent;
+use crate::game::menu::click_event::{OnClickEvent, PartySlot};
 use crate::game::menu::item::MenuItem;
 use crate::game::menu::{MenuMovement, MenuScreen};
 use crate::game::transition::{Transition, TransitionStyle};
-use crate::webgl::audio::Audio;
 
-pub fn none_menu(_party: &mut Vec<Character>) -> MenuScreen {
+// the spoils screen counts up the labels it finds among the unselectables at these positions
+const SPOILS_EXPERIENCE: usize = 1;
+const SPOILS_GOLD: usize = 4;
+const SPOILS_WALLET: usize = 5;
+// every party member then gets a name, an experience and a level label
+const SPOILS_MEMBERS_START: usize = 6;
+const SPOILS_LABELS_PER_MEMBER: usize = 3;
+
+pub fn none_menu(_party: &mut [Character], _inventory: &Inventory) -> MenuScreen {
   MenuScreen::new(Vec::new(), Vec::new(), Vec::new(), MenuMovement::Grid, 0, 0, OnClickEvent::MenuTransition(|_transition: &mut Transition| ()))
 }
 
-pub fn title_menu(_party: &mut Vec<Character>) -> MenuScreen {
+pub fn title_menu(_party: &mut [Character], _inventory: &Inventory) -> MenuScreen {
   let to_debug_room = |transition: &mut Transition| transition.set(TransitionStyle::ChangeScene(maps::debug_room));
+  let continue_game = |transition: &mut Transition| transition.set(TransitionStyle::LoadGame);
   let selectables = vec![
     vec![MenuItem::new(String::from("New Game"),   476., 400., OnClickEvent::None)],
-    vec![MenuItem::new(String::from("Continue"),   476., 432., OnClickEvent::None)],
+    vec![MenuItem::new(String::from("Continue"),   476., 432., OnClickEvent::ChangeScene(continue_game))],
     vec![MenuItem::new(String::from("Debug room"), 456., 464., OnClickEvent::ChangeScene(to_debug_room))]
   ];
   MenuScreen::new(Vec::new(), selectables, Vec::new(), MenuMovement::Grid, 0, 2, OnClickEvent::None)
 }
 
-pub fn main_menu(_party: &mut Vec<Character>) -> MenuScreen {
-  let to_item_menu = |transition: &mut Transition| transition.set(TransitionStyle::MenuIn(item_menu));
-  let exit_menu    = |transition: &mut Transition| transition.set(TransitionStyle::MenuIn(none_menu));
+pub fn game_over(_party: &mut [Character], _inventory: &Inventory, _spoils: &BattleSpoils) -> MenuScreen {
+  let load_last_save = |transition: &mut Transition| transition.set(TransitionStyle::LoadGame);
+  let to_title = |transition: &mut Transition| transition.set(TransitionStyle::ToTitle);
+  let selectables = vec![
+    vec![MenuItem::new(String::from("Load last save"), 436., 400., OnClickEvent::MenuTransition(load_last_save))],
+    vec![MenuItem::new(String::from("Return to title"), 426., 432., OnClickEvent::MenuTransition(to_title))]
+  ];
+  let unselectables = vec![MenuItem::new(String::from("Game Over"), 466., 300., OnClickEvent::None)];
+  MenuScreen::new(Vec::new(), selectables, unselectables, MenuMovement::Grid, 0, 0, OnClickEvent::None)
+}
+
+pub fn main_menu(_party: &mut [Character], inventory: &Inventory) -> MenuScreen {
+  let to_item_menu  = |transition: &mut Transition| transition.set(TransitionStyle::MenuIn(item_menu));
+  let to_equip_menu = |transition: &mut Transition| transition.set(TransitionStyle::MenuIn(equip_menu));
+  let exit_menu     = |transition: &mut Transition| transition.set(TransitionStyle::MenuIn(none_menu));
   let containers = vec![
     MenuContainer::new(16.,  16., 232.,  256.),
+    MenuContainer::new(16.,  600., 232., 704.),
     MenuContainer::new(256., 16., 1064., 704.)
   ];
   let selectables = vec![
     vec![MenuItem::new(String::from("Item"),    70., 48.,  OnClickEvent::MenuTransition(to_item_menu))],
     vec![MenuItem::new(String::from("Skill"),   70., 80.,  OnClickEvent::None)],
-    vec![MenuItem::new(String::from("Equip"),   70., 112., OnClickEvent::None)],
-    vec![MenuItem::new(String::from("Change"),  70., 144., OnClickEvent::None)],
-    vec![MenuItem::new(String::from("Config"),  70., 176., OnClickEvent::None)]
+    vec![MenuItem::new(String::from("Equip"),   70., 112., OnClickEvent::MenuTransition(to_equip_menu))],
+    vec![MenuItem::new(String::from("Change"),  70., 144., OnClickEvent::ToChangeMenu)],
+    vec![MenuItem::new(String::from("Config"),  70., 176., OnClickEvent::ToConfigMenu)]
+  ];
+  let unselectables = vec![
+    MenuItem::new(String::from("Gold"),            40., 620., OnClickEvent::None),
+    MenuItem::new(inventory.get_gold().to_string(), 40., 656., OnClickEvent::None)
   ];
-  let unselectables = Vec::new();
   MenuScreen::new(containers, selectables, unselectables, MenuMovement::Grid, 0, 0, OnClickEvent::MenuTransition(exit_menu))
 }
 
-pub fn item_menu(_party: &mut Vec<Character>) -> MenuScreen {
+// the active party on the left and the reserves on the right, each with an empty place at the end
+pub fn change_menu(party: &[Character], reserves: &[Character], selected: Option<PartySlot>, cursor: PartySlot) -> MenuScreen {
+  let back_to_main_menu = |transition: &mut Transition| transition.set(TransitionStyle::MenuIn(main_menu));
+  let mut selectables = vec![Vec::new(), Vec::new()];
+  let mut unselectables = vec![
+    MenuItem::new(String::from("Change"),   70.,  48.,  OnClickEvent::None),
+    MenuItem::new(String::from("Party"),    70.,  112., OnClickEvent::None),
+    MenuItem::new(String::from("Reserves"), 600., 112., OnClickEvent::None)
+  ];
+  let columns = [
+    (0..MAX_PARTY_SIZE).map(PartySlot::Active).collect::<Vec<PartySlot>>(),
+    (0..reserves.len() + 1).map(PartySlot::Reserve).collect::<Vec<PartySlot>>()
+  ];
+  for (column, slots) in columns.iter().enumerate() {
+    let x = 70. + column as f32 * 530.;
+    for (i, slot) in slots.iter().enumerate() {
+      let y = 176. + i as f32 * 64.;
+      let character = match slot {
+        PartySlot::Active(index) => party.get(*index),
+        PartySlot::Reserve(index) => reserves.get(*index)
+      };
+      let name = character.map_or(String::from("-"), |character: &Character| character.get_name());
+      let mut menu_item = MenuItem::new(name, x, y, OnClickEvent::ChangeMember(selected, *slot));
+      menu_item.set_enabled(!character.is_some_and(|character: &Character| character.is_locked()));
+      selectables[column].push(menu_item);
+      if let Some(character) = character {
+        unselectables.push(MenuItem::new(format!("Lv{:>3}", character.get_battle_state().get_level()), x + 260., y, OnClickEvent::None));
+      }
+      if selected == Some(*slot) {
+        unselectables.push(MenuItem::new(String::from("*"), x + 220., y, OnClickEvent::None));
+      }
+    }
+  }
+  let (cursor_x, cursor_y) = match cursor {
+    PartySlot::Active(index) => (index, 0),
+    PartySlot::Reserve(index) => (index, 1)
+  };
+  MenuScreen::new(
+    vec![MenuContainer::new(16., 16., 1064., 704.)],
+    selectables,
+    unselectables,
+    MenuMovement::RowOfColumns,
+    cursor_x,
+    cursor_y,
+    OnClickEvent::MenuTransition(back_to_main_menu)
+  )
+}
+
+pub fn config_menu(config: &Config, cursor_y: usize) -> MenuScreen {
   let back_to_main_menu = |transition: &mut Transition| transition.set(TransitionStyle::MenuIn(main_menu));
-  MenuScreen::new(vec![MenuContainer::new(16., 16., 1064., 704.)], Vec::new(), Vec::new(), MenuMovement::Grid, 0, 0, OnClickEvent::MenuTransition(back_to_main_menu))
+  let cycle_atb_mode     = |config: &mut Config| config.cycle_atb_mode();
+  let cycle_battle_speed = |config: &mut Config| config.cycle_battle_speed();
+  let selectables = vec![
+    vec![MenuItem::new(String::from("ATB Mode"),     70., 128., OnClickEvent::ChangeConfig(cycle_atb_mode, 0))],
+    vec![MenuItem::new(String::from("Battle Speed"), 70., 192., OnClickEvent::ChangeConfig(cycle_battle_speed, 1))]
+  ];
+  let unselectables = vec![
+    MenuItem::new(String::from("Config"),                         70.,  48.,  OnClickEvent::None),
+    MenuItem::new(config.get_atb_mode().get_name(),               400., 128., OnClickEvent::None),
+    MenuItem::new(format!("{}x", config.get_battle_speed()),      400., 192., OnClickEvent::None)
+  ];
+  MenuScreen::new(vec![MenuContainer::new(16., 16., 1064., 704.)], selectables, unselectables, MenuMovement::Grid, 0, cursor_y, OnClickEvent::MenuTransition(back_to_main_menu))
+}
+
+pub fn item_menu(_party: &mut [Character], inventory: &Inventory) -> MenuScreen {
+  let back_to_main_menu = |transition: &mut Transition| transition.set(TransitionStyle::MenuIn(main_menu));
+  let mut selectables = Vec::new();
+  let mut unselectables = Vec::new();
+  for (i, (item, quantity)) in inventory.get_items().iter().enumerate() {
+    let y = 48. + i as f32 * 32.;
+    selectables.push(vec![MenuItem::new(item.get_name(), 70., y, OnClickEvent::ToItemTargetSelection(item_target_menu, item.get_name()))]);
+    unselectables.push(MenuItem::new(format!("{:>2}", quantity), 960., y, OnClickEvent::None));
+  }
+  MenuScreen::new(vec![MenuContainer::new(16., 16., 1064., 704.)], selectables, unselectables, MenuMovement::Grid, 0, 0, OnClickEvent::MenuTransition(back_to_main_menu))
+}
+
+pub fn item_target_menu(party: &[Character], _enemies: &mut [Vec<Enemy>], item_name: String) -> MenuScreen {
+  let mut selectables = Vec::new();
+  let mut unselectables = vec![MenuItem::new(item_name.to_owned(), 70., 48., OnClickEvent::None)];
+  for (i, character) in party.iter().enumerate() {
+    let y = 128. + i as f32 * 64.;
+    let state = character.get_battle_state();
+    selectables.push(vec![MenuItem::new(character.get_name(), 70., y, OnClickEvent::UseItem(item_name.to_owned(), character.get_id()))]);
+    unselectables.push(MenuItem::new(format!("{:>4}/{:>4} HP", state.get_hp(), state.get_max_hp()), 560., y, OnClickEvent::None));
+    unselectables.push(MenuItem::new(format!("{:>3}/{:>3} MP", state.get_mp(), state.get_max_mp()), 820., y, OnClickEvent::None));
+    let statuses: Vec<String> = state.get_statuses().iter().map(|(status, _): &(StatusEffect, u32)| status.get_short_name()).collect();
+    unselectables.push(MenuItem::new(statuses.join(" "), 330., y, OnClickEvent::None));
+  }
+  MenuScreen::new(vec![MenuContainer::new(16., 16., 1064., 704.)], selectables, unselectables, MenuMovement::Grid, 0, 0, OnClickEvent::SetMenu(item_menu))
+}
+
+pub fn equip_menu(party: &mut [Character], _inventory: &Inventory) -> MenuScreen {
+  let back_to_main_menu = |transition: &mut Transition| transition.set(TransitionStyle::MenuIn(main_menu));
+  let mut selectables = Vec::new();
+  for (i, character) in party.iter().enumerate() {
+    selectables.push(vec![MenuItem::new(character.get_name(), 70., 128. + i as f32 * 64., OnClickEvent::SetCharacterMenu(equipment_slot_menu, character.get_id()))]);
+  }
+  let unselectables = vec![MenuItem::new(String::from("Equip"), 70., 48., OnClickEvent::None)];
+  MenuScreen::new(vec![MenuContainer::new(16., 16., 1064., 704.)], selectables, unselectables, MenuMovement::Grid, 0, 0, OnClickEvent::MenuTransition(back_to_main_menu))
+}
+
+pub fn equipment_slot_menu(party: &mut [Character], _inventory: &Inventory, character_id: usize) -> MenuScreen {
+  let character = party.iter().find(|character: &&Character| character.get_id() == character_id).unwrap();
+  let mut selectables = Vec::new();
+  let mut unselectables = vec![MenuItem::new(character.get_name(), 70., 48., OnClickEvent::None)];
+  for (i, slot) in EquipmentSlot::all().iter().enumerate() {
+    let y = 128. + i as f32 * 64.;
+    let equipment_name = character.get_equipment(*slot).map_or(String::from("-"), |equipment: &Equipment| equipment.get_name());
+    selectables.push(vec![MenuItem::new(slot.get_name(), 70., y, OnClickEvent::ToEquipmentSelection(character_id, *slot))]);
+    unselectables.push(MenuItem::new(equipment_name, 400., y, OnClickEvent::None));
+  }
+  push_stats_to_unselectables(&mut unselectables, character.get_battle_state(), 400.);
+  MenuScreen::new(
+    vec![MenuContainer::new(16., 16., 1064., 352.), MenuContainer::new(16., 368., 1064., 704.)],
+    selectables,
+    unselectables,
+    MenuMovement::Grid,
+    0,
+    0,
+    OnClickEvent::SetMenu(equip_menu)
+  )
+}
+
+pub fn equipment_selection_menu(party: &mut [Character], inventory: &Inventory, character_id: usize, slot: EquipmentSlot) -> MenuScreen {
+  let character = party.iter().find(|character: &&Character| character.get_id() == character_id).unwrap();
+  let state = character.get_battle_state();
+  let current_equipment = character.get_equipment(slot);
+  let mut candidates = inventory.get_equipment_for_slot(slot).into_iter().map(Some).collect::<Vec<Option<&Equipment>>>();
+  if current_equipment.is_some() {
+    candidates.insert(0, None);
+  }
+
+  let mut selectables = Vec::new();
+  let mut unselectables = vec![MenuItem::new(format!("{} - {}", character.get_name(), slot.get_name()), 70., 48., OnClickEvent::None)];
+  for (i, stat_name) in ["ATT", "DEF", "MAG", "RES", "AGI"].iter().enumerate() {
+    unselectables.push(MenuItem::new(stat_name.to_string(), 560. + i as f32 * 100., 96., OnClickEvent::None));
+  }
+  for (i, candidate) in candidates.into_iter().enumerate() {
+    let y = 128. + i as f32 * 32.;
+    let (text, equipment_name) = match candidate {
+      Some(equipment) => (equipment.get_name(), Some(equipment.get_name())),
+      None => (String::from("Remove"), None)
+    };
+    selectables.push(vec![MenuItem::new(text, 70., y, OnClickEvent::Equip(character_id, slot, equipment_name))]);
+    for (j, value) in get_stats_with_equipment(state, current_equipment, candidate).iter().enumerate() {
+      unselectables.push(MenuItem::new(format!("{:>3.0}", value), 560. + j as f32 * 100., y, OnClickEvent::None));
+    }
+  }
+  push_stats_to_unselectables(&mut unselectables, state, 400.);
+  MenuScreen::new(
+    vec![MenuContainer::new(16., 16., 1064., 352.), MenuContainer::new(16., 368., 1064., 704.)],
+    selectables,
+    unselectables,
+    MenuMovement::Grid,
+    0,
+    0,
+    OnClickEvent::SetCharacterMenu(equipment_slot_menu, character_id)
+  )
+}
+
+fn push_stats_to_unselectables(unselectables: &mut Vec<MenuItem>, state: &BattleState, value_x: f32) {
+  let stats = [
+    ("Max HP",     state.get_max_hp() as f64),
+    ("Max MP",     state.get_max_mp() as f64),
+    ("Attack",     state.get_total_attack_stat()),
+    ("Defence",    state.get_total_defence_stat()),
+    ("Magic",      state.get_total_magic_stat()),
+    ("Resistance", state.get_total_resistance_stat()),
+    ("Agility",    state.get_total_agility_stat())
+  ];
+  for (i, (name, value)) in stats.iter().enumerate() {
+    let y = 400. + i as f32 * 32.;
+    unselectables.push(MenuItem::new(name.to_string(), 70., y, OnClickEvent::None));
+    unselectables.push(MenuItem::new(format!("{:>3.0}", value), value_x, y, OnClickEvent::None));
+  }
 }
 
-pub fn battle_won(party: &mut Vec<Character>, mut experience: u32) -> MenuScreen {
-  let start_exp_count = |audio: &mut Audio, menu: &mut MenuScreen, party: &mut Vec<Character>| {
+fn get_stats_with_equipment(state: &BattleState, current_equipment: Option<&Equipment>, new_equipment: Option<&Equipment>) -> [f64; 5] {
+  let no_bonuses = StatBonuses::default();
+  let current = current_equipment.map_or(&no_bonuses, |equipment: &Equipment| equipment.get_bonuses());
+  let new = new_equipment.map_or(&no_bonuses, |equipment: &Equipment| equipment.get_bonuses());
+  [
+    state.get_total_attack_stat() + new.att - current.att,
+    (state.get_total_defence_stat() + new.def - current.def).max(1.),
+    state.get_total_magic_stat() + new.mag - current.mag,
+    (state.get_total_resistance_stat() + new.res - current.res).max(1.),
+    (state.get_total_agility_stat() + new.agi - current.agi).max(1.)
+  ]
+}
+
+fn spoils_member_label(character: &Character, offset: usize) -> usize {
+  SPOILS_MEMBERS_START + (character.get_id() - 1) * SPOILS_LABELS_PER_MEMBER + offset
+}
+
+pub fn battle_won(party: &mut [Character], inventory: &Inventory, spoils: &BattleSpoils) -> MenuScreen {
+  let start_exp_count = |audio: &mut dyn AudioPlayer, rng: &mut dyn Rng, menu: &mut MenuScreen, party: &mut [Character]| {
     
-    let finish_exp_count = |audio: &mut Audio, menu: &mut MenuScreen, party: &mut Vec<Character>| {
-      let exp_left = menu.get_unselectable(1).get_text().parse::<u32>().unwrap();
+    let finish_exp_count = |audio: &mut dyn AudioPlayer, rng: &mut dyn Rng, menu: &mut MenuScreen, party: &mut [Character]| {
+      let exp_left = menu.get_unselectable(SPOILS_EXPERIENCE).get_text().parse::<u32>().unwrap();
       let alive_count = alive_members_count!(party);
-      if exp_left > 0 {
+      let gold_left = menu.get_unselectable(SPOILS_GOLD).get_text().parse::<u32>().unwrap();
+      if exp_left > 0 || gold_left > 0 {
         audio.play_sfx("counter_tick");
-        menu.get_unselectable(1).set_text(0.to_string());
+        menu.get_unselectable(SPOILS_EXPERIENCE).set_text(0.to_string());
         for character in iter_alive_members!(party) {
-          character.get_battle_state_mut().add_experience(exp_left / alive_count);
-          menu.get_unselectable(character.get_id() * 2 + 2).set_text(character.get_battle_state().get_experience().to_string());
+          add_battle_experience(menu, character, exp_left / alive_count, rng);
         }
+        count_battle_gold(menu, gold_left);
       } else {
         menu.end_mutation();
       }
@@ -64,41 +287,96 @@ pub fn battle_won(party: &mut Vec<Character>, mut experience: u32) -> MenuScreen
       menu.get_selectable(0, 0).set_click_event(OnClickEvent::MenuTransition(exit_menu));
     };
 
-    let exp_left = menu.get_unselectable(1).get_text().parse::<u32>().unwrap();
+    let exp_left = menu.get_unselectable(SPOILS_EXPERIENCE).get_text().parse::<u32>().unwrap();
+    let gold_left = menu.get_unselectable(SPOILS_GOLD).get_text().parse::<u32>().unwrap();
     let alive_count = alive_members_count!(party);
-    if exp_left > 0 {
+    if exp_left > 0 || gold_left > 0 {
       audio.play_sfx("counter_tick");
-      menu.get_unselectable(1).set_text(format!("{}", exp_left - alive_count));
-      for character in iter_alive_members!(party) {
-        character.get_battle_state_mut().add_experience(1);
-        menu.get_unselectable(character.get_id() * 2 + 2).set_text(character.get_battle_state().get_experience().to_string());
+      if exp_left > 0 {
+        menu.get_unselectable(SPOILS_EXPERIENCE).set_text(format!("{}", exp_left - alive_count));
+        for character in iter_alive_members!(party) {
+          add_battle_experience(menu, character, 1, rng);
+        }
       }
+      count_battle_gold(menu, gold_left.min(1));
       menu.get_selectable(0, 0).set_click_event(OnClickEvent::MutateMenu(finish_exp_count));
     } else {
-      finish_exp_count(audio, menu, party);
+      finish_exp_count(audio, rng, menu, party);
     }
   };
   let containers = vec![
     MenuContainer::new(50.,  10.,  550.,  110.),
+    MenuContainer::new(570., 10.,  1070., 110.),
     MenuContainer::new(10.,  120., 1070., 360.),
     MenuContainer::new(50.,  370., 250.,  470.),
     MenuContainer::new(10.,  480., 1070., 600.),
     MenuContainer::new(760., 610., 1040., 710.)
   ];
   let alive_count = alive_members_count!(party);
-  if experience % alive_count != 0 {
-    experience += alive_count - experience % alive_count;
-  }
+  let experience = spoils.experience.next_multiple_of(alive_count);
   let selectables = vec![vec![MenuItem::new(String::from("Continue"), 825., 650., OnClickEvent::MutateMenu(start_exp_count))]];
 
-  let mut unselectables = Vec::new();
-  unselectables.push(MenuItem::new(String::from("Experience"), 90.,  50.,  OnClickEvent::None));
-  unselectables.push(MenuItem::new(experience.to_string(),     350., 50.,  OnClickEvent::None));
-  unselectables.push(MenuItem::new(String::from("Items"),      90.,  410., OnClickEvent::None));
+  let mut unselectables = vec![
+    MenuItem::new(String::from("Experience"),                                  90.,  50.,  OnClickEvent::None),
+    MenuItem::new(experience.to_string(),                                      350., 50.,  OnClickEvent::None),
+    MenuItem::new(String::from("Items"),                                       90.,  410., OnClickEvent::None),
+    MenuItem::new(String::from("Gold"),                                        610., 50.,  OnClickEvent::None),
+    MenuItem::new(spoils.gold.to_string(),                                     730., 50.,  OnClickEvent::None),
+    MenuItem::new(inventory.get_gold().to_string(),                            890., 50.,  OnClickEvent::None)
+  ];
+  debug_assert_eq!(unselectables.len(), SPOILS_MEMBERS_START);
 
   for (character, (x, y)) in party.iter().zip([(50., 160.), (590., 160.), (50., 260.), (590., 260.)].iter()) {
     unselectables.push(MenuItem::new(character.get_name(), *x, *y, OnClickEvent::None));
     unselectables.push(MenuItem::new(character.get_battle_state().get_experience().to_string(), x + 250., *y, OnClickEvent::None));
+    unselectables.push(MenuItem::new(format!("Lv {}", character.get_battle_state().get_level()), *x, y + 40., OnClickEvent::None));
+  }
+
+  let mut item_counts: Vec<(&String, u32)> = Vec::new();
+  for item_name in spoils.items.iter() {
+    match item_counts.iter_mut().find(|(name, _): &&mut (&String, u32)| *name == item_name) {
+      Some((_, count)) => *count += 1,
+      None => item_counts.push((item_name, 1))
+    }
+  }
+  for (i, (item_name, count)) in item_counts.iter().enumerate() {
+    unselectables.push(MenuItem::new(format!("{} x{}", item_name, count), 50. + (i % 3) as f32 * 340., 500. + (i / 3) as f32 * 40., OnClickEvent::None));
   }
   MenuScreen::new(containers, selectables, unselectables, MenuMovement::Grid, 0, 0, OnClickEvent::None)
 }
+
+fn count_battle_gold(menu: &mut MenuScreen, gold: u32) {
+  let gold_left = menu.get_unselectable(SPOILS_GOLD).get_text().parse::<u32>().unwrap();
+  let wallet = menu.get_unselectable(SPOILS_WALLET).get_text().parse::<u32>().unwrap();
+  menu.get_unselectable(SPOILS_GOLD).set_text((gold_left - gold).to_string());
+  menu.get_unselectable(SPOILS_WALLET).set_text(wallet.saturating_add(gold).min(MAX_GOLD).to_string());
+}
+
+fn add_battle_experience(menu: &mut MenuScreen, character: &mut Character, experience: u32, rng: &mut dyn Rng) {
+  let gains = character.get_battle_state_mut().add_experience(experience, Some(rng));
+  let state = character.get_battle_state();
+  menu.get_unselectable(spoils_member_label(character, 1)).set_text(state.get_experience().to_string());
+  if gains.levels > 0 {
+    menu.get_unselectable(spoils_member_label(character, 2)).set_text(format!("Lv {} {}", state.get_level(), gains.get_summary()));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::data::content::Content;
+
+  #[test]
+  fn spoils_wallet_counts_up_to_the_gold_cap() {
+    let content = Content::default_content();
+    let mut party = content.create_starting_party();
+    let mut inventory = Inventory::new();
+    inventory.add_gold(MAX_GOLD - 10);
+    let spoils = BattleSpoils { experience: 0, gold: 300, items: Vec::new() };
+    let mut menu = battle_won(&mut party, &inventory, &spoils);
+    assert_eq!(menu.get_unselectable(SPOILS_WALLET).get_text(), (MAX_GOLD - 10).to_string());
+    count_battle_gold(&mut menu, 300);
+    assert_eq!(menu.get_unselectable(SPOILS_GOLD).get_text(), "0");
+    assert_eq!(menu.get_unselectable(SPOILS_WALLET).get_text(), MAX_GOLD.to_string());
+  }
+}
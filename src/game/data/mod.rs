@@ -1,8 +1,5 @@
 pub mod battle_actions;
 pub mod battle_menus;
-pub mod characters;
-pub mod enemies;
-pub mod enemy_formations;
+pub mod content;
 pub mod maps;
 pub mod menus;
-pub mod npcs;
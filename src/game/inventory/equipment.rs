@@ -0,0 +1,81 @@
+use serde::Deserialize;
+use crate::game::battle::element::{Affinity, Element};
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EquipmentSlot {
+  Weapon, Armour, Accessory
+}
+
+impl EquipmentSlot {
+  pub fn all() -> [EquipmentSlot; 3] {
+    [EquipmentSlot::Weapon, EquipmentSlot::Armour, EquipmentSlot::Accessory]
+  }
+
+  pub fn get_name(&self) -> String {
+    match self {
+      EquipmentSlot::Weapon    => String::from("Weapon"),
+      EquipmentSlot::Armour    => String::from("Armour"),
+      EquipmentSlot::Accessory => String::from("Accessory")
+    }
+  }
+}
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatBonuses {
+  pub hp: u16,
+  pub mp: u16,
+  pub att: f64,
+  pub def: f64,
+  pub mag: f64,
+  pub res: f64,
+  pub agi: f64
+}
+
+impl StatBonuses {
+  pub fn add_bonuses(&mut self, other: &StatBonuses) {
+    self.hp = self.hp.saturating_add(other.hp);
+    self.mp = self.mp.saturating_add(other.mp);
+    self.att += other.att;
+    self.def += other.def;
+    self.mag += other.mag;
+    self.res += other.res;
+    self.agi += other.agi;
+  }
+}
+
+#[derive(Clone)]
+pub struct Equipment {
+  name: String,
+  slot: EquipmentSlot,
+  bonuses: StatBonuses,
+  affinities: Vec<(Element, Affinity)>
+}
+
+impl Equipment {
+  pub fn new(name: String, slot: EquipmentSlot, bonuses: StatBonuses, affinities: Vec<(Element, Affinity)>) -> Self {
+    Self {
+      name,
+      slot,
+      bonuses,
+      affinities
+    }
+  }
+
+  pub fn get_name(&self) -> String {
+    self.name.to_owned()
+  }
+
+  pub fn get_slot(&self) -> EquipmentSlot {
+    self.slot
+  }
+
+  pub fn get_bonuses(&self) -> &StatBonuses {
+    &self.bonuses
+  }
+
+  pub fn get_affinities(&self) -> &Vec<(Element, Affinity)> {
+    &self.affinities
+  }
+}
@@ -0,0 +1,63 @@
+use serde::Deserialize;
+use crate::game::battle::TargetAction;
+use crate::game::battle::element::Element;
+use crate::game::battle::state::BattleState;
+use crate::game::battle::status::{StatusChange, StatusEffect};
+
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ItemEffect {
+  RestoreHp { amount: u16 },
+  RestoreMp { amount: u16 },
+  CureStatus { statuses: Vec<StatusEffect> }
+}
+
+impl ItemEffect {
+  pub fn get_target_action(&self) -> Option<(TargetAction, f64)> {
+    match self {
+      ItemEffect::RestoreHp { amount } => Some((|target_state: &mut BattleState, value: f64, _element: Option<Element>| target_state.restore_hp(value as u16), *amount as f64)),
+      ItemEffect::RestoreMp { amount } => Some((|target_state: &mut BattleState, value: f64, _element: Option<Element>| target_state.restore_mp(value as u16), *amount as f64)),
+      ItemEffect::CureStatus { .. } => None
+    }
+  }
+
+  pub fn get_status_change(&self) -> Option<StatusChange> {
+    match self {
+      ItemEffect::CureStatus { statuses } => Some(StatusChange::Cure { statuses: statuses.clone() }),
+      _ => None
+    }
+  }
+
+  pub fn can_target(&self, target_state: &BattleState) -> bool {
+    match self {
+      ItemEffect::RestoreHp { .. } => target_state.can_restore_hp(),
+      ItemEffect::RestoreMp { .. } => target_state.can_restore_mp(),
+      ItemEffect::CureStatus { statuses } => {
+        target_state.get_hp() > 0 && statuses.iter().any(|status: &StatusEffect| target_state.has_status(*status))
+      }
+    }
+  }
+}
+
+#[derive(Clone)]
+pub struct Item {
+  name: String,
+  effect: ItemEffect
+}
+
+impl Item {
+  pub fn new(name: String, effect: ItemEffect) -> Self {
+    Self {
+      name,
+      effect
+    }
+  }
+
+  pub fn get_name(&self) -> String {
+    self.name.to_owned()
+  }
+
+  pub fn get_effect(&self) -> &ItemEffect {
+    &self.effect
+  }
+}
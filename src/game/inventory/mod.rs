@@ -0,0 +1,102 @@
+pub mod equipment;
+pub mod item;
+
+use equipment::{Equipment, EquipmentSlot};
+use crate::game::battle::character::Character;
+use item::Item;
+
+const MAX_ITEM_QUANTITY: u32 = 99;
+
+pub const MAX_GOLD: u32 = 9_999_999;
+
+pub struct Inventory {
+  items: Vec<(Item, u32)>,
+  equipment: Vec<(Equipment, u32)>,
+  gold: u32,
+  // benched party members travel with the rest of what the party carries
+  reserves: Vec<Character>
+}
+
+impl Inventory {
+  pub fn new() -> Self {
+    Self {
+      items: Vec::new(),
+      equipment: Vec::new(),
+      gold: 0,
+      reserves: Vec::new()
+    }
+  }
+
+  pub fn get_reserves(&self) -> &Vec<Character> {
+    &self.reserves
+  }
+
+  pub fn get_reserves_mut(&mut self) -> &mut Vec<Character> {
+    &mut self.reserves
+  }
+
+  pub fn set_reserves(&mut self, reserves: Vec<Character>) {
+    self.reserves = reserves;
+  }
+
+  pub fn get_gold(&self) -> u32 {
+    self.gold
+  }
+
+  pub fn add_gold(&mut self, gold: u32) {
+    self.gold = self.gold.saturating_add(gold).min(MAX_GOLD);
+  }
+
+  pub fn add_item(&mut self, item: Item, quantity: u32) {
+    if let Some((_, count)) = self.items.iter_mut().find(|(owned_item, _): &&mut (Item, u32)| owned_item.get_name() == item.get_name()) {
+      *count = (*count + quantity).min(MAX_ITEM_QUANTITY);
+    } else if quantity > 0 {
+      self.items.push((item, quantity.min(MAX_ITEM_QUANTITY)));
+    }
+  }
+
+  pub fn take_item(&mut self, name: &str) -> Option<Item> {
+    let index = self.items.iter().position(|(item, _): &(Item, u32)| item.get_name() == name)?;
+    self.items[index].1 -= 1;
+    if self.items[index].1 == 0 {
+      return Some(self.items.remove(index).0);
+    }
+    Some(self.items[index].0.clone())
+  }
+
+  pub fn get_item(&self, name: &str) -> Option<&Item> {
+    self.items.iter().find(|(item, _): &&(Item, u32)| item.get_name() == name).map(|(item, _): &(Item, u32)| item)
+  }
+
+  pub fn get_items(&self) -> &Vec<(Item, u32)> {
+    &self.items
+  }
+
+  pub fn add_equipment(&mut self, equipment: Equipment, quantity: u32) {
+    if let Some((_, count)) = self.equipment.iter_mut().find(|(owned, _): &&mut (Equipment, u32)| owned.get_name() == equipment.get_name()) {
+      *count = (*count + quantity).min(MAX_ITEM_QUANTITY);
+    } else if quantity > 0 {
+      self.equipment.push((equipment, quantity.min(MAX_ITEM_QUANTITY)));
+    }
+  }
+
+  pub fn take_equipment(&mut self, name: &str) -> Option<Equipment> {
+    let index = self.equipment.iter().position(|(equipment, _): &(Equipment, u32)| equipment.get_name() == name)?;
+    self.equipment[index].1 -= 1;
+    if self.equipment[index].1 == 0 {
+      return Some(self.equipment.remove(index).0);
+    }
+    Some(self.equipment[index].0.clone())
+  }
+
+  pub fn get_equipment(&self) -> &Vec<(Equipment, u32)> {
+    &self.equipment
+  }
+
+  pub fn get_equipment_for_slot(&self, slot: EquipmentSlot) -> Vec<&Equipment> {
+    self.equipment.iter()
+      .filter(|(equipment, _): &&(Equipment, u32)| equipment.get_slot() == slot)
+      .map(|(equipment, _): &(Equipment, u32)| equipment)
+      .collect()
+  }
+}
@@ -0,0 +1,63 @@
+use crate::game::backend::Rng;
+
+pub struct EncounterTable {
+  rate: f64,
+  formations: Vec<(String, f64)>
+}
+
+impl EncounterTable {
+  pub fn new(rate: f64, formations: Vec<(String, f64)>) -> Self {
+    Self {
+      rate,
+      formations
+    }
+  }
+
+  pub fn none() -> Self {
+    EncounterTable::new(0., Vec::new())
+  }
+
+  // Parses a comma separated list of formation names with optional weights, e.g. "bats:3, slime".
+  pub fn parse(rate: f64, formations: &str) -> Result<Self, String> {
+    let mut weighted_formations = Vec::new();
+    for entry in formations.split(',').map(|entry: &str| entry.trim()).filter(|entry: &&str| !entry.is_empty()) {
+      let (name, weight) = match entry.find(':') {
+        Some(index) => {
+          let weight = entry[index + 1..].trim().parse::<f64>().map_err(|_| format!("Invalid encounter weight in '{}'", entry))?;
+          (entry[..index].trim(), weight)
+        },
+        None => (entry, 1.)
+      };
+      if weight <= 0. {
+        return Err(format!("Encounter weight must be positive in '{}'", entry));
+      }
+      weighted_formations.push((name.to_string(), weight));
+    }
+    if !(0. ..=1.).contains(&rate) {
+      return Err(format!("Encounter rate {} is not between 0 and 1", rate));
+    }
+    if rate > 0. && weighted_formations.is_empty() {
+      return Err(String::from("Encounter rate is set but no encounter formations are listed"));
+    }
+    Ok(EncounterTable::new(rate, weighted_formations))
+  }
+
+  pub fn get_formation_names(&self) -> Vec<&str> {
+    self.formations.iter().map(|(name, _): &(String, f64)| name.as_str()).collect()
+  }
+
+  pub fn roll(&self, rng: &mut dyn Rng) -> Option<String> {
+    if self.formations.is_empty() || rng.random() >= self.rate {
+      return None;
+    }
+    let total_weight = self.formations.iter().fold(0., |total: f64, (_, weight): &(String, f64)| total + weight);
+    let mut roll = rng.random() * total_weight;
+    for (name, weight) in self.formations.iter() {
+      if roll < *weight {
+        return Some(name.to_owned());
+      }
+      roll -= weight;
+    }
+    self.formations.last().map(|(name, _): &(String, f64)| name.to_owned())
+  }
+}
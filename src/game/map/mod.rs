@@ -1,63 +1,78 @@
+pub mod encounter;
 pub mod npc;
 pub mod player;
 mod tile;
+mod tiled;
 mod tilegrid;
 
+use encounter::EncounterTable;
 use npc::Npc;
 use tile::Tile;
 use tilegrid::TileGrid;
-use crate::game::battle::enemy::Enemy;
-use crate::webgl::audio::Audio;
-use crate::webgl::shader_program::ShaderProgram;
-use js_sys::Math::random;
+use crate::game::backend::{AudioPlayer, Renderer, Rng};
 
 pub struct Map {
+  name: String,
   soundtrack_file: String,
   tiles:  TileGrid,
   npcs: Vec<Npc>,
-  encounter_function: fn(f64) -> Option<Vec<Vec<Enemy>>>
+  encounters: EncounterTable
 }
 
 impl Map {
   pub fn new(
+    name: String,
     tileset_folder: String,
     soundtrack_file: String,
     tile_keys_and_blocks: Vec<Vec<(&str, bool)>>,
     npcs: Vec<Npc>,
-    encounter_function: fn(f64) -> Option<Vec<Vec<Enemy>>>
+    encounters: EncounterTable
   ) -> Self {
     let mut tiles = Vec::new();
     for (y, row) in tile_keys_and_blocks.iter().enumerate() {
       let mut tile_row = Vec::new();
       for (x, (key, blocking)) in row.iter().enumerate() {
-        tile_row.push(Tile::new(format!("tilesets/{}/{}", tileset_folder, key), x, y, *blocking));
+        tile_row.push(Tile::new(vec![format!("tilesets/{}/{}", tileset_folder, key)], x, y, *blocking));
       }
       tiles.push(tile_row);
     }
+    Map::from_tiles(name, soundtrack_file, tiles, npcs, encounters)
+  }
+
+  fn from_tiles(name: String, soundtrack_file: String, mut tiles: Vec<Vec<Tile>>, npcs: Vec<Npc>, encounters: EncounterTable) -> Self {
     for npc in npcs.iter() {
       tiles[npc.get_y()][npc.get_x()].set_occupied();
     }
     Self {
+      name,
       soundtrack_file,
       tiles: TileGrid::new(tiles),
       npcs,
-      encounter_function
+      encounters
     }
   }
 
-  pub fn update(&mut self, audio: &mut Audio) {
+  pub fn update(&mut self, audio: &mut dyn AudioPlayer, rng: &mut dyn Rng) {
     audio.update(&self.soundtrack_file);
     for npc in self.npcs.iter_mut() {
-      npc.update(&mut self.tiles);
+      npc.update(rng, &mut self.tiles);
     }
   }
 
   pub fn set_map(&mut self, new_map: Map) {
     *self = new_map;
   }
+
+  pub fn get_name(&self) -> String {
+    self.name.to_owned()
+  }
   
-  pub fn get_encounter(&self) -> Option<Vec<Vec<Enemy>>> {
-    (self.encounter_function)(random())
+  pub fn get_encounter(&self, rng: &mut dyn Rng) -> Option<String> {
+    self.encounters.roll(rng)
+  }
+
+  pub fn get_encounter_formations(&self) -> Vec<&str> {
+    self.encounters.get_formation_names()
   }
 
   pub fn set_occupied(&mut self, x: usize, y: usize) {
@@ -72,7 +87,7 @@ impl Map {
     self.tiles.is_tile_empty(x, y)
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram, player_coords: (f32, f32)) {
+  pub fn draw(&self, program: &mut dyn Renderer, player_coords: (f32, f32)) {
     self.tiles.draw(program, player_coords);
     for npc in self.npcs.iter() {
       npc.draw(program, player_coords)
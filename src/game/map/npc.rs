@@ -1,10 +1,9 @@
 use crate::game::animation::{Animation, Direction};
 use crate::game::animation::character::CharacterAnimation;
+use crate::game::backend::{Renderer, Rng};
 use crate::game::map::player::Player;
 use crate::game::map::tilegrid::TileGrid;
 use crate::game::menu::textbox::Textbox;
-use crate::webgl::shader_program::ShaderProgram;
-use js_sys::Math::random;
 
 pub struct Npc {
   animation: CharacterAnimation,
@@ -35,10 +34,10 @@ impl Npc {
     }
   }
 
-  pub fn update(&mut self, tiles: &mut TileGrid) {
+  pub fn update(&mut self, rng: &mut dyn Rng, tiles: &mut TileGrid) {
     if !self.stationary {
       if self.can_walk {
-        let random_value = random();
+        let random_value = rng.random();
         if random_value < 0.01 {
           self.direction = Direction::Up;
           self.move_tile(tiles);
@@ -118,7 +117,7 @@ impl Npc {
     self.y
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram, player_coords: (f32, f32)) {
+  pub fn draw(&self, program: &mut dyn Renderer, player_coords: (f32, f32)) {
     self.animation.draw(
       program,
       540. + self.x as f32 * 64. - player_coords.0 + self.x_sub,
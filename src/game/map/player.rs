@@ -1,13 +1,14 @@
 use crate::game::animation::{Animation, Direction};
 use crate::game::animation::character::CharacterAnimation;
+use crate::game::backend::{Input, Renderer, Rng};
 use crate::game::battle::Battle;
 use crate::game::battle::character::Character;
+use crate::game::data::content::Content;
 use crate::game::map::Map;
 use crate::game::map::npc::Npc;
 use crate::game::menu::textbox::Textbox;
 use crate::game::transition::Transition;
-use crate::webgl::keyboard::{is_pressed, is_down};
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::GameData;
 
 pub struct Player {
   animation: CharacterAnimation,
@@ -32,30 +33,38 @@ impl Player {
     }
   }
 
-  pub fn update(&mut self, map: &mut Map, party: &mut Vec<Character>, battle: &mut Battle, transition: &mut Transition, textbox: &mut Textbox) {
+  pub fn update(
+    &mut self,
+    input: &mut dyn Input,
+    rng: &mut dyn Rng,
+    map: &mut Map,
+    battle: &mut Battle,
+    transition: &mut Transition,
+    game_data: &mut GameData
+  ) {
     if self.can_walk {
-      if is_pressed("a") {
+      if input.is_pressed("a") {
         let (x, y) = self.get_position_in_front();
-        self.attempt_interaction(map, textbox, x, y);
+        self.attempt_interaction(map, &mut game_data.textbox, x, y);
 
-      } else if is_down("up") {
+      } else if input.is_down("up") {
         self.direction = Direction::Up;
         self.move_tile(map);
 
-      } else if is_down("down") {
+      } else if input.is_down("down") {
         self.direction = Direction::Down;
         self.move_tile(map);
 
-      } else if is_down("left") {
+      } else if input.is_down("left") {
         self.direction = Direction::Left;
         self.move_tile(map);
 
-      } else if is_down("right") {
+      } else if input.is_down("right") {
         self.direction = Direction::Right;
         self.move_tile(map);
       }
     } else {
-      self.finish_walking(map, party, battle, transition);
+      self.finish_walking(rng, &game_data.content, map, &mut game_data.party, battle, transition);
     }
   }
 
@@ -70,7 +79,7 @@ impl Player {
     }
   }
 
-  fn finish_walking(&mut self, map: &mut Map, party: &mut Vec<Character>, battle: &mut Battle, transition: &mut Transition) {
+  fn finish_walking(&mut self, rng: &mut dyn Rng, content: &Content, map: &mut Map, party: &mut [Character], battle: &mut Battle, transition: &mut Transition) {
     let animation_finished = self.animation.advance_animation();
     match self.direction {
       Direction::Up    => self.y_sub -= 8.,
@@ -89,7 +98,7 @@ impl Player {
         Direction::Left  => self.x -= 1,
         Direction::Right => self.x += 1
       };
-      if let Some(enemies) = map.get_encounter() {
+      if let Some(enemies) = map.get_encounter(rng).and_then(|formation: String| content.create_formation(&formation)) {
         battle.start_battle(party, enemies, transition);
       }
     }
@@ -115,6 +124,10 @@ impl Player {
     (self.x as f32 * 64. + self.x_sub, self.y as f32 * 64. + self.y_sub)
   }
 
+  pub fn get_position(&self) -> (usize, usize) {
+    (self.x, self.y)
+  }
+
   pub fn get_position_in_front(&self) -> (usize, usize) {
     match self.direction {
       Direction::Up    => (self.x, self.y - 1),
@@ -128,7 +141,7 @@ impl Player {
     self.direction
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram) {
+  pub fn draw(&self, program: &mut dyn Renderer) {
     self.animation.draw(program, 540., 360.);
   }
 }
@@ -1,7 +1,7 @@
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::backend::Renderer;
 
 pub struct Tile {
-  sprite_key: String,
+  sprite_keys: Vec<String>,
   x: usize,
   y: usize,
   blocking: bool,
@@ -9,9 +9,9 @@ pub struct Tile {
 }
 
 impl Tile {
-  pub fn new(sprite_key: String, x: usize, y: usize, blocking: bool) -> Self {
+  pub fn new(sprite_keys: Vec<String>, x: usize, y: usize, blocking: bool) -> Self {
     Self {
-      sprite_key: sprite_key.to_owned(),
+      sprite_keys,
       x,
       y,
       blocking,
@@ -34,14 +34,16 @@ impl Tile {
     true
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram, player_coords: (f32, f32)) {
-    program.draw(
-      self.sprite_key.to_owned(),
-      540. + self.x as f32 * 64. - player_coords.0,
-      360. + self.y as f32 * 64. - player_coords.1,
-      64.,
-      64.,
-      1.
-    );
+  pub fn draw(&self, program: &mut dyn Renderer, player_coords: (f32, f32)) {
+    for sprite_key in self.sprite_keys.iter() {
+      program.draw(
+        sprite_key.to_owned(),
+        540. + self.x as f32 * 64. - player_coords.0,
+        360. + self.y as f32 * 64. - player_coords.1,
+        64.,
+        64.,
+        1.
+      );
+    }
   }
 }
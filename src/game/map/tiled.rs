@@ -0,0 +1,250 @@
+use serde::Deserialize;
+use serde_json::Value;
+use crate::game::animation::Direction;
+use crate::game::map::Map;
+use crate::game::map::encounter::EncounterTable;
+use crate::game::map::npc::Npc;
+use crate::game::map::player::Player;
+use crate::game::map::tile::Tile;
+
+// Tiled stores flip flags in the three highest bits of every tile gid.
+const GID_MASK: u32 = 0x1FFF_FFFF;
+const COLLISION_LAYER: &str = "collision";
+
+#[derive(Deserialize)]
+struct TiledMap {
+  width: usize,
+  height: usize,
+  tilewidth: f32,
+  tileheight: f32,
+  orientation: String,
+  #[serde(default)]
+  infinite: bool,
+  layers: Vec<TiledLayer>,
+  tilesets: Vec<TiledTileset>,
+  #[serde(default)]
+  properties: Vec<TiledProperty>
+}
+
+#[derive(Deserialize)]
+struct TiledLayer {
+  name: String,
+  #[serde(rename = "type")]
+  layer_type: String,
+  #[serde(default)]
+  encoding: Option<String>,
+  #[serde(default)]
+  data: Vec<u32>,
+  #[serde(default)]
+  objects: Vec<TiledObject>
+}
+
+#[derive(Deserialize)]
+struct TiledObject {
+  name: String,
+  #[serde(rename = "type", default)]
+  object_type: String,
+  #[serde(default)]
+  class: String,
+  x: f32,
+  y: f32,
+  #[serde(default)]
+  properties: Vec<TiledProperty>
+}
+
+#[derive(Deserialize)]
+struct TiledTileset {
+  firstgid: u32,
+  #[serde(default)]
+  name: String,
+  #[serde(default)]
+  source: Option<String>,
+  #[serde(default)]
+  tiles: Vec<TiledTile>
+}
+
+#[derive(Deserialize)]
+struct TiledTile {
+  id: u32,
+  image: String
+}
+
+#[derive(Deserialize)]
+struct TiledProperty {
+  name: String,
+  value: Value
+}
+
+fn find_property<'a>(properties: &'a [TiledProperty], name: &str) -> Option<&'a Value> {
+  properties.iter().find(|property: &&TiledProperty| property.name == name).map(|property: &TiledProperty| &property.value)
+}
+
+fn string_property(properties: &[TiledProperty], name: &str, owner: &str) -> Result<Option<String>, String> {
+  match find_property(properties, name) {
+    Some(Value::String(value)) => Ok(Some(value.to_owned())),
+    Some(_) => Err(format!("Property '{}' of {} must be a string", name, owner)),
+    None => Ok(None)
+  }
+}
+
+impl TiledObject {
+  fn get_class(&self) -> &str {
+    if self.class.is_empty() {
+      &self.object_type
+    } else {
+      &self.class
+    }
+  }
+
+  fn get_tile_position(&self, map: &TiledMap) -> Result<(usize, usize), String> {
+    let x = (self.x / map.tilewidth).floor();
+    let y = (self.y / map.tileheight).floor();
+    if x < 0. || y < 0. || x as usize >= map.width || y as usize >= map.height {
+      return Err(format!("Object '{}' is outside the map", self.name));
+    }
+    Ok((x as usize, y as usize))
+  }
+}
+
+impl TiledMap {
+  fn get_tile_sprite_key(&self, gid: u32) -> Result<Option<String>, String> {
+    let gid = gid & GID_MASK;
+    if gid == 0 {
+      return Ok(None);
+    }
+    let tileset = self.tilesets.iter()
+      .filter(|tileset: &&TiledTileset| tileset.firstgid <= gid)
+      .max_by_key(|tileset: &&TiledTileset| tileset.firstgid)
+      .ok_or_else(|| format!("Tile gid {} does not belong to any tileset", gid))?;
+    let tile = tileset.tiles.iter()
+      .find(|tile: &&TiledTile| tile.id == gid - tileset.firstgid)
+      .ok_or_else(|| format!("Tile gid {} is missing from tileset '{}'", gid, tileset.name))?;
+    let file_name = tile.image.rsplit('/').next().unwrap();
+    let sprite_name = match file_name.rfind('.') {
+      Some(index) => &file_name[..index],
+      None => file_name
+    };
+    Ok(Some(format!("tilesets/{}/{}", tileset.name, sprite_name)))
+  }
+}
+
+impl Map {
+  pub fn from_tiled(name: String, json: &str, player: &mut Player) -> Result<Self, String> {
+    let tiled_map: TiledMap = serde_json::from_str(json).map_err(|error| format!("Invalid Tiled map '{}': {}", name, error))?;
+    if tiled_map.orientation != "orthogonal" || tiled_map.infinite {
+      return Err(format!("Tiled map '{}' must be a finite orthogonal map", name));
+    }
+    if let Some(tileset) = tiled_map.tilesets.iter().find(|tileset: &&TiledTileset| tileset.source.is_some()) {
+      return Err(format!("Tiled map '{}' uses external tileset '{}', embed it in the map instead", name, tileset.source.as_ref().unwrap()));
+    }
+
+    let mut sprite_keys = vec![vec![Vec::new(); tiled_map.width]; tiled_map.height];
+    let mut blocking = vec![vec![false; tiled_map.width]; tiled_map.height];
+    for layer in tiled_map.layers.iter().filter(|layer: &&TiledLayer| layer.layer_type == "tilelayer") {
+      if let Some(encoding) = &layer.encoding {
+        if encoding != "csv" {
+          return Err(format!("Layer '{}' of Tiled map '{}' must use CSV tile layer format", layer.name, name));
+        }
+      }
+      if layer.data.len() != tiled_map.width * tiled_map.height {
+        return Err(format!("Layer '{}' of Tiled map '{}' does not match the map size", layer.name, name));
+      }
+      for (index, gid) in layer.data.iter().enumerate() {
+        let (x, y) = (index % tiled_map.width, index / tiled_map.width);
+        if layer.name == COLLISION_LAYER {
+          blocking[y][x] = *gid != 0;
+        } else if let Some(sprite_key) = tiled_map.get_tile_sprite_key(*gid)? {
+          sprite_keys[y][x].push(sprite_key);
+        }
+      }
+    }
+    // the edge of the map always blocks, so nobody can walk or look past it
+    for (y, row) in blocking.iter_mut().enumerate() {
+      for (x, is_blocking) in row.iter_mut().enumerate() {
+        let on_edge = x == 0 || y == 0 || x + 1 == tiled_map.width || y + 1 == tiled_map.height;
+        *is_blocking = *is_blocking || on_edge || sprite_keys[y][x].is_empty();
+      }
+    }
+    let tiles = sprite_keys.into_iter().enumerate().map(|(y, row): (usize, Vec<Vec<String>>)| {
+      row.into_iter().enumerate().map(|(x, keys): (usize, Vec<String>)| Tile::new(keys, x, y, blocking[y][x])).collect()
+    }).collect();
+
+    let mut npcs = Vec::new();
+    let mut spawn = None;
+    for object in tiled_map.layers.iter().filter(|layer: &&TiledLayer| layer.layer_type == "objectgroup").flat_map(|layer: &TiledLayer| layer.objects.iter()) {
+      let (x, y) = object.get_tile_position(&tiled_map)?;
+      let owner = format!("object '{}'", object.name);
+      if blocking[y][x] {
+        return Err(format!("Object '{}' in Tiled map '{}' stands on a blocking tile", object.name, name));
+      }
+      match object.get_class() {
+        "npc" => {
+          let sprite_folder = string_property(&object.properties, "sprite_folder", &owner)?
+            .ok_or_else(|| format!("Npc '{}' has no sprite_folder property", object.name))?;
+          let stationary = find_property(&object.properties, "stationary").and_then(|value: &Value| value.as_bool()).unwrap_or(true);
+          let texts = string_property(&object.properties, "texts", &owner)?.unwrap_or_default();
+          npcs.push(Npc::new(sprite_folder, object.name.to_owned(), x, y, stationary, texts.lines().map(String::from).collect()));
+        },
+        "spawn" => {
+          let direction = match string_property(&object.properties, "direction", &owner)?.as_deref() {
+            Some("Up")    => Direction::Up,
+            Some("Left")  => Direction::Left,
+            Some("Right") => Direction::Right,
+            Some("Down") | None => Direction::Down,
+            Some(other) => return Err(format!("Spawn '{}' has unknown direction '{}'", object.name, other))
+          };
+          spawn = Some((x, y, direction));
+        },
+        other => return Err(format!("Object '{}' in Tiled map '{}' has unknown type '{}'", object.name, name, other))
+      }
+    }
+    let (spawn_x, spawn_y, spawn_direction) = spawn.ok_or_else(|| format!("Tiled map '{}' has no spawn object", name))?;
+    player.set(spawn_x, spawn_y, spawn_direction);
+
+    let owner = format!("Tiled map '{}'", name);
+    let soundtrack_file = string_property(&tiled_map.properties, "soundtrack", &owner)?.unwrap_or_default();
+    let encounter_rate = find_property(&tiled_map.properties, "encounter_rate").and_then(|value: &Value| value.as_f64()).unwrap_or(0.);
+    let encounter_formations = string_property(&tiled_map.properties, "encounters", &owner)?.unwrap_or_default();
+    let encounters = EncounterTable::parse(encounter_rate, &encounter_formations).map_err(|error: String| format!("{}: {}", owner, error))?;
+
+    Ok(Map::from_tiles(name, soundtrack_file, tiles, npcs, encounters))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // a 4x4 floor with the spawn object at the given tile
+  fn floor_map(spawn_x: usize, spawn_y: usize) -> String {
+    serde_json::json!({
+      "width": 4, "height": 4, "tilewidth": 64.0, "tileheight": 64.0, "orientation": "orthogonal",
+      "layers": [
+        {"name": "ground", "type": "tilelayer", "data": vec![1; 16]},
+        {"name": "objects", "type": "objectgroup", "objects": [
+          {"name": "start", "type": "spawn", "x": spawn_x as f32 * 64., "y": spawn_y as f32 * 64.}
+        ]}
+      ],
+      "tilesets": [{"firstgid": 1, "name": "test", "tiles": [{"id": 0, "image": "floor.png"}]}]
+    }).to_string()
+  }
+
+  #[test]
+  fn map_edge_blocks_even_on_walkable_tiles() {
+    let mut player = Player::new();
+    let map = Map::from_tiled(String::from("floor"), &floor_map(1, 1), &mut player).unwrap();
+    assert!(map.is_tile_empty(1, 1));
+    assert!(map.is_tile_empty(2, 2));
+    assert!(!map.is_tile_empty(0, 1));
+    assert!(!map.is_tile_empty(1, 0));
+    assert!(!map.is_tile_empty(3, 2));
+    assert!(!map.is_tile_empty(2, 3));
+  }
+
+  #[test]
+  fn spawn_on_a_blocking_tile_is_rejected() {
+    let mut player = Player::new();
+    let error = Map::from_tiled(String::from("floor"), &floor_map(0, 2), &mut player).err().unwrap();
+    assert_eq!(error, "Object 'start' in Tiled map 'floor' stands on a blocking tile");
+  }
+}
@@ -1,5 +1,5 @@
+use crate::game::backend::Renderer;
 use crate::game::map::tile::Tile;
-use crate::webgl::shader_program::ShaderProgram;
 
 pub struct TileGrid {
   tiles: Vec<Vec<Tile>>
@@ -20,11 +20,12 @@ impl TileGrid {
     self.tiles[y][x].set_unoccupied();
   }
 
+  // anything past the edge of the grid counts as blocked
   pub fn is_tile_empty(&self, x: usize, y: usize) -> bool {
-    self.tiles[y][x].is_walkable()
+    self.tiles.get(y).and_then(|row: &Vec<Tile>| row.get(x)).is_some_and(|tile: &Tile| tile.is_walkable())
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram, player_coords: (f32, f32)) {
+  pub fn draw(&self, program: &mut dyn Renderer, player_coords: (f32, f32)) {
     for row in self.tiles.iter() {
       for tile in row.iter() {
         tile.draw(program, player_coords);
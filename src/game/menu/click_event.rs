@@ -1,30 +1,50 @@
-use crate::game::battle::ActionTuple;
-use crate::game::battle::BattleActionTargetStart;
+use crate::game::animation::Animation;
+use crate::game::backend::Rng;
+use crate::game::battle::ability::Ability;
 use crate::game::battle::character::Character;
 use crate::game::battle::enemy::Enemy;
-use crate::game::data::battle_menus;
+use crate::game::config::Config;
+use crate::game::data::{battle_menus, menus};
+use crate::game::data::battle_menus::{BattleActionFunction, TargetingMenu};
+use crate::game::inventory::Inventory;
+use crate::game::inventory::equipment::EquipmentSlot;
 use crate::game::menu::{MenuScreen, MenuMutation};
 use crate::game::menu::notification::Notification;
 use crate::game::transition::Transition;
+use crate::game::GameData;
+
+// the change menu's places, empty places past the end of either list are valid too
+#[derive(Clone, Copy, PartialEq)]
+pub enum PartySlot {
+  Active(usize),
+  Reserve(usize)
+}
 
 pub enum OnClickEvent {
   MenuTransition(for<'a> fn(&'a mut Transition)),
   MutateMenu(MenuMutation),
   SetBattleMenu(for<'a> fn(&'a Character) -> MenuScreen),
-  ToTargetSelection(for<'r, 's> fn(
-    &'r Vec<Character>,
-    &'s mut Vec<Vec<Enemy>>,
-    ActionTuple, BattleActionTargetStart) -> MenuScreen,
-    ActionTuple,
-    BattleActionTargetStart
-  ),
-  BattleAction(for<'a, 'b, 'c> fn(&'a mut Vec<Character>, &'b mut Vec<Vec<Enemy>>, Vec<usize>, ActionTuple, &'c mut Notification), Vec<usize>, ActionTuple),
+  ToTargetSelection(TargetingMenu, Ability),
+  BattleAction(BattleActionFunction, Vec<usize>, Ability),
+  SetMenu(for<'a, 'b> fn(&'a mut [Character], &'b Inventory) -> MenuScreen),
+  ToItemTargetSelection(for<'r, 's> fn(&'r [Character], &'s mut [Vec<Enemy>], String) -> MenuScreen, String),
+  UseItem(String, usize),
+  UseBattleItem(String, usize),
+  SetCharacterMenu(for<'a, 'b> fn(&'a mut [Character], &'b Inventory, usize) -> MenuScreen, usize),
+  ToEquipmentSelection(usize, EquipmentSlot),
+  Equip(usize, EquipmentSlot, Option<String>),
+  Defend,
+  ChangeRow,
   ChangeScene(for<'a> fn(&'a mut Transition)),
+  ToConfigMenu,
+  ChangeConfig(for<'a> fn(&'a mut Config), usize),
+  ToChangeMenu,
+  ChangeMember(Option<PartySlot>, PartySlot),
   None
 }
 
 pub enum ClickEventReturnType {
-  NewMenu(MenuScreen),
+  NewMenu(Box<MenuScreen>),
   StartMutation(MenuMutation),
   None
 }
@@ -40,26 +60,281 @@ impl OnClickEvent {
 
 pub fn match_click_event(
   event: &OnClickEvent,
-  party: &mut Vec<Character>,
-  enemies: &mut Vec<Vec<Enemy>>,
+  rng: &mut dyn Rng,
+  enemies: &mut [Vec<Enemy>],
   transition: &mut Transition,
-  notification: &mut Notification
+  game_data: &mut GameData
 ) -> ClickEventReturnType {
+  let GameData { party, inventory, notification, config, .. } = game_data;
   match event {
     OnClickEvent::MenuTransition(to_new_menu)    => to_new_menu(transition),
     OnClickEvent::MutateMenu(mutation_function)  => return ClickEventReturnType::StartMutation(*mutation_function),
-    OnClickEvent::SetBattleMenu(new_battle_menu) => return ClickEventReturnType::NewMenu(
+    OnClickEvent::SetBattleMenu(new_battle_menu) => return ClickEventReturnType::NewMenu(Box::new(
       new_battle_menu(party.iter().find(|character: &&Character| character.get_battle_state().is_turn_active()).unwrap())
-    ),
-    OnClickEvent::ToTargetSelection(to_target_selection, action_effects, targeting_start_type) => {
-      return ClickEventReturnType::NewMenu(to_target_selection(party, enemies, *action_effects, *targeting_start_type))
+    )),
+    OnClickEvent::ToTargetSelection(to_target_selection, ability) => {
+      return ClickEventReturnType::NewMenu(Box::new(to_target_selection(party, enemies, ability.clone())))
+    },
+    OnClickEvent::BattleAction(action, target_ids, ability) => {
+      action(rng, party, enemies, inventory, target_ids.to_vec(), ability.clone(), notification);
+      return ClickEventReturnType::NewMenu(Box::new(battle_menus::none_menu()));
     },
-    OnClickEvent::BattleAction(action, target_ids, action_effects) => {
-      action(party, enemies, target_ids.to_vec(), *action_effects, notification);
-      return ClickEventReturnType::NewMenu(battle_menus::none_menu());
+    OnClickEvent::SetMenu(new_menu) => return ClickEventReturnType::NewMenu(Box::new(new_menu(party, inventory))),
+    OnClickEvent::ToItemTargetSelection(to_target_selection, item_name) => {
+      return ClickEventReturnType::NewMenu(Box::new(to_target_selection(party, enemies, item_name.to_owned())))
+    },
+    OnClickEvent::UseItem(item_name, target_id) => return use_item(rng, party, inventory, enemies, notification, item_name, *target_id),
+    OnClickEvent::UseBattleItem(item_name, target_id) => return use_battle_item(rng, party, inventory, enemies, item_name, *target_id),
+    OnClickEvent::SetCharacterMenu(new_menu, character_id) => return ClickEventReturnType::NewMenu(Box::new(new_menu(party, inventory, *character_id))),
+    OnClickEvent::ToEquipmentSelection(character_id, slot) => {
+      return ClickEventReturnType::NewMenu(Box::new(menus::equipment_selection_menu(party, inventory, *character_id, *slot)))
+    },
+    OnClickEvent::Equip(character_id, slot, equipment_name) => return equip(party, inventory, *character_id, *slot, equipment_name),
+    OnClickEvent::Defend => {
+      let acting_character = party.iter_mut().find(|character: &&mut Character| character.get_battle_state().is_turn_active()).unwrap();
+      acting_character.defend();
+      notification.set_notification(String::from("Defend"));
+      return ClickEventReturnType::NewMenu(Box::new(battle_menus::none_menu()));
+    },
+    OnClickEvent::ChangeRow => {
+      let acting_character = party.iter_mut().find(|character: &&mut Character| character.get_battle_state().is_turn_active()).unwrap();
+      acting_character.change_row();
+      notification.set_notification(String::from("Row"));
+      return ClickEventReturnType::NewMenu(Box::new(battle_menus::none_menu()));
     },
     OnClickEvent::ChangeScene(to_new_map) => to_new_map(transition),
+    OnClickEvent::ToConfigMenu => return ClickEventReturnType::NewMenu(Box::new(menus::config_menu(config, 0))),
+    OnClickEvent::ChangeConfig(change_config, cursor_y) => {
+      change_config(config);
+      return ClickEventReturnType::NewMenu(Box::new(menus::config_menu(config, *cursor_y)));
+    },
+    OnClickEvent::ToChangeMenu => return ClickEventReturnType::NewMenu(Box::new(menus::change_menu(party, inventory.get_reserves(), None, PartySlot::Active(0)))),
+    OnClickEvent::ChangeMember(selected, slot) => return change_member(party, inventory.get_reserves_mut(), notification, *selected, *slot),
     OnClickEvent::None => ()
   };
   ClickEventReturnType::None
-}
\ No newline at end of file
+}
+
+fn use_item(
+  rng: &mut dyn Rng,
+  party: &mut [Character],
+  inventory: &mut Inventory,
+  enemies: &mut [Vec<Enemy>],
+  notification: &mut Notification,
+  item_name: &str,
+  target_id: usize
+) -> ClickEventReturnType {
+  if let Some(effect) = inventory.get_item(item_name).map(|item| item.get_effect().clone()) {
+    let target = party.iter_mut().find(|character: &&mut Character| character.get_id() == target_id).unwrap();
+    if effect.can_target(target.get_battle_state()) {
+      inventory.take_item(item_name);
+      if let Some(status_change) = effect.get_status_change() {
+        target.get_battle_state_mut().apply_status_change(&status_change, rng);
+      }
+      if let Some((target_action, value)) = effect.get_target_action() {
+        target_action(target.get_battle_state_mut(), value, None);
+      }
+    } else {
+      notification.set_notification(format!("{} has no effect", item_name));
+    }
+  }
+  if inventory.get_item(item_name).is_some() {
+    ClickEventReturnType::NewMenu(Box::new(menus::item_target_menu(party, enemies, item_name.to_string())))
+  } else {
+    ClickEventReturnType::NewMenu(Box::new(menus::item_menu(party, inventory)))
+  }
+}
+
+fn use_battle_item(
+  rng: &mut dyn Rng,
+  party: &mut [Character],
+  inventory: &mut Inventory,
+  enemies: &mut [Vec<Enemy>],
+  item_name: &str,
+  target_id: usize
+) -> ClickEventReturnType {
+  let acting_character = party.iter_mut().find(|character: &&mut Character| character.get_battle_state().is_turn_active()).unwrap();
+  let item = match inventory.take_item(item_name) {
+    Some(item) => item,
+    None => return ClickEventReturnType::NewMenu(Box::new(battle_menus::main_battle_menu(acting_character)))
+  };
+  acting_character.perform_battle_action(Animation::Attack);
+
+  let status_change = item.get_effect().get_status_change();
+  let target_action = item.get_effect().get_target_action();
+  for character in party.iter_mut() {
+    if character.get_id() == target_id {
+      if let Some(status_change) = &status_change {
+        character.get_battle_state_mut().apply_status_change(status_change, rng);
+      }
+      if let Some((target_action, value)) = target_action {
+        character.receive_battle_action(target_action, value, None);
+      }
+    }
+  }
+  for enemy in enemies.iter_mut().flatten() {
+    if enemy.get_id() == target_id {
+      if let Some(status_change) = &status_change {
+        enemy.get_battle_state_mut().apply_status_change(status_change, rng);
+      }
+      if let Some((target_action, value)) = target_action {
+        enemy.receive_battle_action(target_action, value, None);
+      }
+    }
+  }
+  ClickEventReturnType::NewMenu(Box::new(battle_menus::none_menu()))
+}
+
+fn equip(
+  party: &mut [Character],
+  inventory: &mut Inventory,
+  character_id: usize,
+  slot: EquipmentSlot,
+  equipment_name: &Option<String>
+) -> ClickEventReturnType {
+  let character = party.iter_mut().find(|character: &&mut Character| character.get_id() == character_id).unwrap();
+  let previous_equipment = match equipment_name {
+    Some(name) => match inventory.take_equipment(name) {
+      Some(equipment) => character.equip(equipment),
+      None => None
+    },
+    None => character.unequip(slot)
+  };
+  if let Some(equipment) = previous_equipment {
+    inventory.add_equipment(equipment, 1);
+  }
+  ClickEventReturnType::NewMenu(Box::new(menus::equipment_slot_menu(party, inventory, character_id)))
+}
+
+fn change_member(
+  party: &mut Vec<Character>,
+  reserves: &mut Vec<Character>,
+  notification: &mut Notification,
+  selected: Option<PartySlot>,
+  slot: PartySlot
+) -> ClickEventReturnType {
+  let selected = match selected {
+    Some(selected) if selected != slot => selected,
+    Some(_) => return ClickEventReturnType::NewMenu(Box::new(menus::change_menu(party, reserves, None, slot))),
+    None => return ClickEventReturnType::NewMenu(Box::new(menus::change_menu(party, reserves, Some(slot), slot)))
+  };
+  if moves_locked_member(party, reserves, selected, slot) {
+    notification.set_notification(String::from("That member can't be moved"));
+    return ClickEventReturnType::NewMenu(Box::new(menus::change_menu(party, reserves, None, slot)));
+  }
+  match (selected, slot) {
+    (PartySlot::Active(a), PartySlot::Active(b)) => {
+      if a < party.len() && b < party.len() {
+        party.swap(a, b);
+      } else if a < party.len() {
+        let character = party.remove(a);
+        party.push(character);
+      } else if b < party.len() {
+        let character = party.remove(b);
+        party.push(character);
+      }
+    },
+    (PartySlot::Reserve(a), PartySlot::Reserve(b)) => {
+      if a < reserves.len() && b < reserves.len() {
+        reserves.swap(a, b);
+      }
+    },
+    (PartySlot::Active(active), PartySlot::Reserve(reserve)) | (PartySlot::Reserve(reserve), PartySlot::Active(active)) => {
+      if active < party.len() && reserve < reserves.len() {
+        std::mem::swap(&mut party[active], &mut reserves[reserve]);
+      } else if reserve < reserves.len() {
+        party.push(reserves.remove(reserve));
+      } else if active < party.len() {
+        if party.len() == 1 {
+          notification.set_notification(String::from("The party needs someone in it"));
+        } else {
+          reserves.push(party.remove(active));
+        }
+      }
+    }
+  }
+  for (i, character) in party.iter_mut().enumerate() {
+    character.set_id(i + 1);
+  }
+  for character in reserves.iter_mut() {
+    character.set_id(0);
+  }
+  ClickEventReturnType::NewMenu(Box::new(menus::change_menu(party, reserves, None, slot)))
+}
+
+// locked members have to keep their place, so shifting anyone behind them is refused too
+fn moves_locked_member(party: &[Character], reserves: &[Character], selected: PartySlot, slot: PartySlot) -> bool {
+  let is_locked = |index: usize| party.get(index).is_some_and(|character: &Character| character.is_locked());
+  let locked_from = |index: usize| party.iter().skip(index).any(|character: &Character| character.is_locked());
+  match (selected, slot) {
+    (PartySlot::Active(a), PartySlot::Active(b)) if a < party.len() && b < party.len() => is_locked(a) || is_locked(b),
+    (PartySlot::Active(a), PartySlot::Active(b)) => locked_from(a.min(b)),
+    (PartySlot::Reserve(_), PartySlot::Reserve(_)) => false,
+    (PartySlot::Active(active), PartySlot::Reserve(reserve)) | (PartySlot::Reserve(reserve), PartySlot::Active(active)) => {
+      if reserve < reserves.len() {
+        is_locked(active)
+      } else {
+        locked_from(active)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::data::content::Content;
+
+  // the default party is Darrel (locked), Seraphine, Darrel (locked), Seraphine with one Seraphine in reserve
+  fn starting_members() -> (Vec<Character>, Vec<Character>) {
+    let content = Content::default_content();
+    (content.create_starting_party(), content.create_starting_reserves())
+  }
+
+  fn move_member(party: &mut Vec<Character>, reserves: &mut Vec<Character>, selected: PartySlot, slot: PartySlot) {
+    let mut notification = Notification::new();
+    change_member(party, reserves, &mut notification, Some(selected), slot);
+  }
+
+  fn locked_ids(party: &[Character]) -> Vec<usize> {
+    party.iter().filter(|character: &&Character| character.is_locked()).map(|character: &Character| character.get_id()).collect()
+  }
+
+  #[test]
+  fn benching_in_front_of_a_locked_member_is_refused() {
+    let (mut party, mut reserves) = starting_members();
+    move_member(&mut party, &mut reserves, PartySlot::Active(1), PartySlot::Reserve(1));
+    assert_eq!(party.len(), 4);
+    assert_eq!(reserves.len(), 1);
+    assert_eq!(locked_ids(&party), vec![1, 3]);
+  }
+
+  #[test]
+  fn moving_to_an_empty_active_slot_in_front_of_a_locked_member_is_refused() {
+    let (mut party, mut reserves) = starting_members();
+    move_member(&mut party, &mut reserves, PartySlot::Active(3), PartySlot::Reserve(1));
+    move_member(&mut party, &mut reserves, PartySlot::Active(1), PartySlot::Active(3));
+    assert_eq!(locked_ids(&party), vec![1, 3]);
+  }
+
+  #[test]
+  fn swapping_a_locked_member_is_refused() {
+    let (mut party, mut reserves) = starting_members();
+    move_member(&mut party, &mut reserves, PartySlot::Active(0), PartySlot::Active(1));
+    move_member(&mut party, &mut reserves, PartySlot::Reserve(0), PartySlot::Active(2));
+    assert_eq!(locked_ids(&party), vec![1, 3]);
+    assert!(!reserves[0].is_locked());
+  }
+
+  #[test]
+  fn unlocked_members_can_still_be_moved() {
+    let (mut party, mut reserves) = starting_members();
+    move_member(&mut party, &mut reserves, PartySlot::Active(3), PartySlot::Reserve(1));
+    assert_eq!(party.len(), 3);
+    assert_eq!(reserves.len(), 2);
+    move_member(&mut party, &mut reserves, PartySlot::Reserve(0), PartySlot::Active(1));
+    assert_eq!(party.len(), 3);
+    assert_eq!(locked_ids(&party), vec![1, 3]);
+    assert!(reserves.iter().all(|character: &Character| character.get_id() == 0));
+  }
+}
@@ -1,4 +1,4 @@
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::backend::Renderer;
 
 pub struct MenuContainer {
   x1: f32,
@@ -17,7 +17,7 @@ impl MenuContainer {
     }
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram) {
+  pub fn draw(&self, program: &mut dyn Renderer) {
     program.draw(String::from("menu/up_left"),     self.x1,       self.y1,       16.,                     16.,                     1.);
     program.draw(String::from("menu/up"),          self.x1 + 16., self.y1,       self.x2 - self.x1 - 32., 16.,                     1.);
     program.draw(String::from("menu/up_right"),    self.x2 - 16., self.y1,       16.,                     16.,                     1.);
@@ -1,6 +1,10 @@
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::backend::Renderer;
 
-pub fn print_text(program: &mut ShaderProgram, text: String, x: f32, y: f32) {
+pub fn print_text(program: &mut dyn Renderer, text: String, x: f32, y: f32) {
+  print_text_with_opacity(program, text, x, y, 1.);
+}
+
+pub fn print_text_with_opacity(program: &mut dyn Renderer, text: String, x: f32, y: f32, opacity: f32) {
   for (index, character) in text.chars().enumerate() {
     let sprite_key = match character {
       '0' => "0",
@@ -85,6 +89,6 @@ pub fn print_text(program: &mut ShaderProgram, text: String, x: f32, y: f32) {
       '*' => "star",
       _ => "spacebar"
     };
-    program.draw(format!("fonts/text/{}", sprite_key), x + index as f32 * 20., y, 20., 20., 1.);
+    program.draw(format!("fonts/text/{}", sprite_key), x + index as f32 * 20., y, 20., 20., opacity);
   }
 }
\ No newline at end of file
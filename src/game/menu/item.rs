@@ -1,16 +1,17 @@
-use crate::game::battle::character::Character;
+use crate::game::backend::{Renderer, Rng};
 use crate::game::battle::enemy::Enemy;
 use crate::game::menu::click_event::{OnClickEvent, ClickEventReturnType, match_click_event};
-use crate::game::menu::font::print_text;
-use crate::game::menu::notification::Notification;
+use crate::game::menu::font::print_text_with_opacity;
 use crate::game::transition::Transition;
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::GameData;
 
 pub struct MenuItem {
   text: String,
   x: f32,
   y: f32,
-  on_click: OnClickEvent
+  on_click: OnClickEvent,
+  enabled: bool,
+  highlights: Vec<(f32, f32)>
 }
 
 impl MenuItem {
@@ -19,18 +20,20 @@ impl MenuItem {
       text,
       x,
       y,
-      on_click
+      on_click,
+      enabled: true,
+      highlights: Vec::new()
     }
   }
 
   pub fn click_item(
     &self,
-    party: &mut Vec<Character>,
-    enemies: &mut Vec<Vec<Enemy>>,
+    rng: &mut dyn Rng,
+    enemies: &mut [Vec<Enemy>],
     transition: &mut Transition,
-    notification: &mut Notification
+    game_data: &mut GameData
   ) -> ClickEventReturnType {
-    match_click_event(&self.on_click, party, enemies, transition, notification)
+    match_click_event(&self.on_click, rng, enemies, transition, game_data)
   }
 
   pub fn set_text(&mut self, new_text: String) {
@@ -41,15 +44,33 @@ impl MenuItem {
     self.text.to_owned()
   }
 
-  pub fn get_coords(&self) -> (f32, f32) {
-    (self.x, self.y)
+  // items that stand for several battlers point the cursor at each of them
+  pub fn set_highlights(&mut self, highlights: Vec<(f32, f32)>) {
+    self.highlights = highlights;
+  }
+
+  pub fn get_cursor_coords(&self) -> Vec<(f32, f32)> {
+    if !self.highlights.is_empty() {
+      self.highlights.to_owned()
+    } else {
+      vec![(self.x, self.y)]
+    }
   }
 
   pub fn set_click_event(&mut self, new_event: OnClickEvent) {
     self.on_click = new_event;
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram) {
-    print_text(program, self.text.to_owned(), self.x, self.y);
+  pub fn set_enabled(&mut self, enabled: bool) {
+    self.enabled = enabled;
+  }
+
+  pub fn is_enabled(&self) -> bool {
+    self.enabled
+  }
+
+  pub fn draw(&self, program: &mut dyn Renderer) {
+    let opacity = if self.enabled { 1. } else { 0.4 };
+    print_text_with_opacity(program, self.text.to_owned(), self.x, self.y, opacity);
   }
 }
@@ -8,19 +8,17 @@ pub mod textbox;
 use click_event::{match_click_event, OnClickEvent, ClickEventReturnType};
 use container::MenuContainer;
 use item::MenuItem;
-use notification::Notification;
+use crate::game::backend::{AudioPlayer, Input, Renderer, Rng};
 use crate::game::battle::character::Character;
 use crate::game::battle::enemy::Enemy;
 use crate::game::transition::Transition;
-use crate::webgl::audio::Audio;
-use crate::webgl::keyboard::is_pressed;
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::GameData;
 
 pub enum MenuMovement {
   Grid, ColumnOfRows, RowOfColumns
 }
 
-pub type MenuMutation = for<'a, 'b, 'c> fn(&'a mut Audio, &'b mut MenuScreen, &'c mut Vec<Character>);
+pub type MenuMutation = for<'a, 'b, 'c, 'd> fn(&'a mut dyn AudioPlayer, &'b mut dyn Rng, &'c mut MenuScreen, &'d mut [Character]);
 
 pub struct MenuScreen {
   containers: Vec<MenuContainer>,
@@ -55,26 +53,38 @@ impl MenuScreen {
     }
   }
 
-  pub fn update(&mut self, audio: &mut Audio, party: &mut Vec<Character>, enemies: &mut Vec<Vec<Enemy>>, transition: &mut Transition, notification: &mut Notification) {
-    if is_pressed("a") {
-      if self.return_action.is_some() {
-        audio.play_sfx("menu_click");
+  pub fn update(
+    &mut self,
+    input: &mut dyn Input,
+    audio: &mut dyn AudioPlayer,
+    rng: &mut dyn Rng,
+    enemies: &mut [Vec<Enemy>],
+    transition: &mut Transition,
+    game_data: &mut GameData
+  ) {
+    if input.is_pressed("a") {
+      if !self.selectables.is_empty() && self.selectables[self.cursor_y][self.cursor_x].is_enabled() {
+        if self.return_action.is_some() {
+          audio.play_sfx("menu_click");
+        }
+        let click_event_return_type = self.selectables[self.cursor_y][self.cursor_x].click_item(rng, enemies, transition, game_data);
+        self.match_click_event_return_type(click_event_return_type);
+      }
+    } else if input.is_pressed("s") {
+      self.perform_return_action(audio, rng, enemies, transition, game_data);
+    } else if !self.selectables.is_empty() {
+      if input.is_pressed("up") {
+        self.move_cursor_up();
+      } else if input.is_pressed("down") {
+        self.move_cursor_down();
+      } else if input.is_pressed("left") {
+        self.move_cursor_left();
+      } else if input.is_pressed("right") {
+        self.move_cursor_right();
       }
-      let click_event_return_type = self.selectables[self.cursor_y][self.cursor_x].click_item(party, enemies, transition, notification);
-      self.match_click_event_return_type(click_event_return_type);
-    } else if is_pressed("s") {
-      self.perform_return_action(audio, party, enemies, transition, notification);
-    } else if is_pressed("up") {
-      self.move_cursor_up();
-    } else if is_pressed("down") {
-      self.move_cursor_down();
-    } else if is_pressed("left") {
-      self.move_cursor_left();
-    } else if is_pressed("right") {
-      self.move_cursor_right();
     }
     if let Some(mutation_function) = &mut self.mutation {
-      mutation_function(audio, self, party);
+      mutation_function(audio, rng, self, &mut game_data.party);
     }
   }
 
@@ -84,16 +94,16 @@ impl MenuScreen {
 
   pub fn perform_return_action(
     &mut self,
-    audio: &mut Audio,
-    party: &mut Vec<Character>,
-    enemies: &mut Vec<Vec<Enemy>>,
+    audio: &mut dyn AudioPlayer,
+    rng: &mut dyn Rng,
+    enemies: &mut [Vec<Enemy>],
     transition: &mut Transition,
-    notification: &mut Notification
+    game_data: &mut GameData
   ) {
     if self.return_action.is_some() {
       audio.play_sfx("menu_click"); // Replace with return sound when available
     }
-    let click_event_return_type = match_click_event(&self.return_action, party, enemies, transition, notification);
+    let click_event_return_type = match_click_event(&self.return_action, rng, enemies, transition, game_data);
     self.match_click_event_return_type(click_event_return_type);
   }
 
@@ -117,7 +127,7 @@ impl MenuScreen {
     &mut self.unselectables[index]
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram) {
+  pub fn draw(&self, program: &mut dyn Renderer) {
     for container in self.containers.iter() {
       container.draw(program);
     }
@@ -128,14 +138,15 @@ impl MenuScreen {
       item.draw(program);
     }
     if self.selectables.len() > 0 {
-      let (x, y) = self.selectables.get(self.cursor_y).unwrap().get(self.cursor_x).unwrap().get_coords();
-      program.draw(String::from("cursor"), x - 40., y, 48., 48., 1.);
+      for (x, y) in self.selectables.get(self.cursor_y).unwrap().get(self.cursor_x).unwrap().get_cursor_coords() {
+        program.draw(String::from("cursor"), x - 40., y, 48., 48., 1.);
+      }
     }
   }
 
   fn match_click_event_return_type(&mut self, click_event_return_type: ClickEventReturnType) {
     match click_event_return_type {
-      ClickEventReturnType::NewMenu(new_menu)                => self.set_menu(new_menu),
+      ClickEventReturnType::NewMenu(new_menu)                => self.set_menu(*new_menu),
       ClickEventReturnType::StartMutation(mutation_function) => self.start_mutation(mutation_function),
       ClickEventReturnType::None => ()
     }
@@ -1,6 +1,6 @@
+use crate::game::backend::Renderer;
 use crate::game::menu::container::MenuContainer;
 use crate::game::menu::font::print_text;
-use crate::webgl::shader_program::ShaderProgram;
 
 pub struct Notification {
   text: String,
@@ -26,7 +26,7 @@ impl Notification {
     self.show_time_left = 60;
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram) {
+  pub fn draw(&self, program: &mut dyn Renderer) {
     if self.show_time_left > 0 {
       MenuContainer::new(16., 16., 1064., 80.).draw(program);
       print_text(program, self.text.to_owned(), 540. - self.text.len() as f32 * 8., 36.);
@@ -1,7 +1,6 @@
+use crate::game::backend::{Input, Renderer};
 use crate::game::menu::container::MenuContainer;
 use crate::game::menu::font::print_text;
-use crate::webgl::keyboard::is_pressed;
-use crate::webgl::shader_program::ShaderProgram;
 
 pub struct Textbox {
   open: bool,
@@ -20,8 +19,8 @@ impl Textbox {
     }
   }
 
-  pub fn update(&mut self) {
-    if is_pressed("a") {
+  pub fn update(&mut self, input: &mut dyn Input) {
+    if input.is_pressed("a") {
       if self.textbox_index + 1 == self.texts.len() {
         self.open = false;
         self.textbox_index = 0;
@@ -41,7 +40,7 @@ impl Textbox {
     self.open
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram) {
+  pub fn draw(&self, program: &mut dyn Renderer) {
     MenuContainer::new(16., 500., 1064., 704.).draw(program);
     print_text(program, self.name.to_owned(), 64., 524.);
     print_text(program, self.texts[self.textbox_index].to_owned(), 40., 556.);
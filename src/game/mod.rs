@@ -1,92 +1,160 @@
 #[macro_use]
 mod party_macros;
 mod animation;
+pub mod backend;
 mod battle;
+mod config;
 mod data;
+mod inventory;
 mod map;
 mod menu;
+mod random;
+mod save;
 mod transition;
 
+use backend::{AudioPlayer, Input, Renderer};
 use battle::Battle;
 use battle::character::Character;
+use config::Config;
+use data::content::Content;
+use inventory::Inventory;
 use map::Map;
 use map::player::Player;
 use menu::MenuScreen;
 use menu::notification::Notification;
 use menu::textbox::Textbox;
+use random::SeededRng;
+use save::SaveData;
 use transition::{Transition, TransitionStyle};
-use crate::webgl::audio::Audio;
-use crate::webgl::keyboard::is_down;
-use crate::webgl::shader_program::ShaderProgram;
+
+// what the map, battles, menus and transitions all read and write
+pub struct GameData {
+  pub party: Vec<Character>,
+  pub inventory: Inventory,
+  pub notification: Notification,
+  pub textbox: Textbox,
+  pub config: Config,
+  pub content: Content
+}
+
+impl GameData {
+  pub fn new(content: Content) -> Self {
+    Self {
+      party: content.create_starting_party(),
+      inventory: content.create_starting_inventory(),
+      notification: Notification::new(),
+      textbox: Textbox::new(),
+      config: Config::new(),
+      content
+    }
+  }
+}
 
 pub struct GameState {
   menu: MenuScreen,
   map: Map,
   player: Player,
-  party: Vec<Character>,
-  _reserves: Vec<Character>,
   battle: Battle,
-  notification: Notification,
-  textbox: Textbox,
-  transition: Transition
+  transition: Transition,
+  game_data: GameData,
+  rng: SeededRng,
+  save: Option<SaveData>
 }
 
 impl GameState {
-  pub fn new() -> Self {
+  pub fn new(seed: u32) -> Self {
     let mut player = Player::new();
     player.set_character_sprites(String::from("Darrel_Deen"));
-    let mut party = vec![data::characters::darrel_deen(1), data::characters::nurse_seraphine(2), data::characters::darrel_deen(3), data::characters::nurse_seraphine(4)];
+    let mut game_data = GameData::new(Content::default_content());
     Self {
-      menu: data::menus::title_menu(&mut party),
+      menu: data::menus::title_menu(&mut game_data.party, &game_data.inventory),
       map: data::maps::none_map(&mut player),
       player,
-      party,
-      _reserves: Vec::new(),
       battle: Battle::new(),
-      notification: Notification::new(),
-      textbox: Textbox::new(),
-      transition: Transition::new()
+      transition: Transition::new(),
+      game_data,
+      rng: SeededRng::new(seed),
+      save: None
     }
   }
 
-  pub fn update(&mut self, audio: &mut Audio) {
+  pub fn update(&mut self, input: &mut dyn Input, audio: &mut dyn AudioPlayer) {
     if self.transition.is_transitioning() {
-      self.transition.update(&mut self.map, &mut self.player, &mut self.party, &mut self.battle, &mut self.menu);
+      self.transition.update(&mut self.map, &mut self.player, &mut self.battle, &mut self.menu, &mut self.game_data, &self.save);
 
     } else if self.menu.is_open() {
-      self.menu.update(audio, &mut self.party, self.battle.get_enemies(), &mut self.transition, &mut self.notification);
+      self.menu.update(input, audio, &mut self.rng, self.battle.get_enemies(), &mut self.transition, &mut self.game_data);
 
     } else if self.battle.is_in_battle() {
-      self.battle.update(audio, &mut self.party, &mut self.transition, &mut self.notification);
+      self.battle.update(input, audio, &mut self.rng, &mut self.transition, &mut self.game_data);
 
-    } else if is_down("f") {
+    } else if input.is_down("f") {
       self.transition.set(TransitionStyle::MenuIn(data::menus::main_menu));
 
-    } else if self.textbox.is_open() {
-      self.textbox.update();
+    } else if self.game_data.textbox.is_open() {
+      self.game_data.textbox.update(input);
 
     } else {
-      self.player.update(&mut self.map, &mut self.party, &mut self.battle, &mut self.transition, &mut self.textbox);
-      self.map.update(audio);
+      self.player.update(input, &mut self.rng, &mut self.map, &mut self.battle, &mut self.transition, &mut self.game_data);
+      self.map.update(audio, &mut self.rng);
+    }
+    self.game_data.notification.update();
+  }
+
+  pub fn get_seed(&self) -> u32 {
+    self.rng.get_seed()
+  }
+
+  pub fn set_seed(&mut self, seed: u32) {
+    self.rng.set_seed(seed);
+  }
+
+  pub fn save_game(&mut self) -> Option<String> {
+    if self.battle.is_in_battle() || self.map.get_name().is_empty() {
+      return None;
+    }
+    let save = SaveData::new(&self.map, &self.player, &self.game_data.party, &self.game_data.inventory);
+    let json = save.to_json();
+    self.save = Some(save);
+    Some(json)
+  }
+
+  pub fn load_game(&mut self, json: &str) -> Result<(), String> {
+    self.save = Some(SaveData::from_json(json, &self.game_data.content)?);
+    Ok(())
+  }
+
+  // debug helper, levels the whole party up to the given level the same way experience does
+  pub fn set_party_level(&mut self, level: u32) {
+    for character in self.game_data.party.iter_mut() {
+      character.get_battle_state_mut().set_level(level, Some(&mut self.rng));
+    }
+  }
+
+  pub fn load_content(&mut self, json: &str) -> Result<(), String> {
+    self.game_data.content = Content::from_json(json)?;
+    if self.map.get_name().is_empty() && !self.battle.is_in_battle() {
+      self.game_data.party = self.game_data.content.create_starting_party();
+      self.game_data.inventory = self.game_data.content.create_starting_inventory();
     }
-    self.notification.update();
+    Ok(())
   }
 
-  pub fn draw(&mut self, program: &mut ShaderProgram) {
+  pub fn draw(&mut self, program: &mut dyn Renderer) {
     if self.menu.is_open() {
       self.menu.draw(program);
 
     } else if self.battle.is_in_battle() {
-      self.battle.draw(program, &self.party);
+      self.battle.draw(program, &self.game_data.party);
       
     } else {
       self.map.draw(program, self.player.get_coords());
       self.player.draw(program);
     }
-    if self.textbox.is_open() {
-      self.textbox.draw(program);
+    if self.game_data.textbox.is_open() {
+      self.game_data.textbox.draw(program);
     }
-    self.notification.draw(program);
+    self.game_data.notification.draw(program);
 
     if self.transition.is_transitioning() {
       self.transition.draw(program);
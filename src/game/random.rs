@@ -0,0 +1,39 @@
+use crate::game::backend::Rng;
+
+// SplitMix64, small and fast enough to be stepped every frame by npcs and battles.
+pub struct SeededRng {
+  seed: u32,
+  state: u64
+}
+
+impl SeededRng {
+  pub fn new(seed: u32) -> Self {
+    Self {
+      seed,
+      state: seed as u64
+    }
+  }
+
+  pub fn get_seed(&self) -> u32 {
+    self.seed
+  }
+
+  pub fn set_seed(&mut self, seed: u32) {
+    self.seed = seed;
+    self.state = seed as u64;
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut value = self.state;
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+  }
+}
+
+impl Rng for SeededRng {
+  fn random(&mut self) -> f64 {
+    (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+  }
+}
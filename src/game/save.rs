@@ -0,0 +1,315 @@
+use serde::{Deserialize, Serialize};
+use crate::game::animation::Direction;
+use crate::game::battle::BattleRow;
+use crate::game::battle::character::Character;
+use crate::game::battle::state::BaseStats;
+use crate::game::battle::status::StatusEffect;
+use crate::game::data::content::{Content, MAX_PARTY_SIZE};
+use crate::game::data::maps;
+use crate::game::inventory::Inventory;
+use crate::game::inventory::equipment::{Equipment, EquipmentSlot};
+use crate::game::inventory::item::Item;
+use crate::game::map::Map;
+use crate::game::map::player::Player;
+
+const SAVE_VERSION: u32 = 8;
+
+#[derive(Serialize, Deserialize)]
+struct CharacterSave {
+  name: String,
+  id: usize,
+  level: u32,
+  experience: u32,
+  hp: u16,
+  #[serde(default)]
+  max_hp: Option<u16>,
+  mp: u16,
+  #[serde(default)]
+  max_mp: Option<u16>,
+  att: f64,
+  def: f64,
+  mag: f64,
+  int: f64,
+  res: f64,
+  agi: f64,
+  #[serde(default)]
+  equipment: Option<Vec<String>>,
+  #[serde(default)]
+  statuses: Vec<(StatusEffect, u32)>,
+  #[serde(default)]
+  row: Option<BattleRow>
+}
+
+impl CharacterSave {
+  fn new(character: &Character) -> Self {
+    let state = character.get_battle_state();
+    CharacterSave {
+      name: character.get_name(),
+      id: character.get_id(),
+      level: state.get_level(),
+      experience: state.get_experience(),
+      hp: state.get_hp(),
+      max_hp: Some(state.get_base_max_hp()),
+      mp: state.get_mp(),
+      max_mp: Some(state.get_base_max_mp()),
+      att: state.get_attack_stat(),
+      def: state.get_defence_stat(),
+      mag: state.get_magic_stat(),
+      int: state.get_intelligence_stat(),
+      res: state.get_resistance_stat(),
+      agi: state.get_agility_stat(),
+      equipment: Some(EquipmentSlot::all().iter()
+        .filter_map(|slot: &EquipmentSlot| character.get_equipment(*slot))
+        .map(|equipment: &Equipment| equipment.get_name())
+        .collect()),
+      statuses: state.get_statuses().to_owned(),
+      row: Some(state.get_row())
+    }
+  }
+
+  fn create_character(&self, content: &Content) -> Result<Character, String> {
+    let mut character = content.create_character(&self.name, self.id)
+      .ok_or_else(|| format!("Save data refers to unknown character '{}'", self.name))?;
+    // saves from before equipment existed keep the starting equipment
+    if let Some(saved_equipment) = &self.equipment {
+      for slot in EquipmentSlot::all().iter() {
+        character.unequip(*slot);
+      }
+      for equipment_name in saved_equipment.iter() {
+        let equipment = content.create_equipment(equipment_name)
+          .ok_or_else(|| format!("Save data refers to unknown equipment '{}'", equipment_name))?;
+        character.equip(equipment);
+      }
+    }
+    // equipment goes on first so hp from equipment bonuses isn't clamped away,
+    // and saves from before max hp existed treat the saved hp as the maximum
+    character.get_battle_state_mut().restore(self.level, self.experience, self.hp, self.mp, BaseStats {
+      hp: self.max_hp.unwrap_or(self.hp),
+      mp: self.max_mp.unwrap_or(self.mp),
+      att: self.att,
+      def: self.def,
+      mag: self.mag,
+      int: self.int,
+      res: self.res,
+      agi: self.agi
+    });
+    character.get_battle_state_mut().restore_statuses(self.statuses.to_owned());
+    if let Some(row) = self.row {
+      character.get_battle_state_mut().set_row(row);
+    }
+    Ok(character)
+  }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveData {
+  version: u32,
+  map: String,
+  player_x: usize,
+  player_y: usize,
+  player_direction: Direction,
+  party: Vec<CharacterSave>,
+  #[serde(default)]
+  reserves: Vec<CharacterSave>,
+  #[serde(default)]
+  inventory: Vec<(String, u32)>,
+  #[serde(default)]
+  gold: u32
+}
+
+impl SaveData {
+  pub fn new(map: &Map, player: &Player, party: &[Character], inventory: &Inventory) -> Self {
+    let (player_x, player_y) = player.get_position();
+    Self {
+      version: SAVE_VERSION,
+      map: map.get_name(),
+      player_x,
+      player_y,
+      player_direction: player.get_direction(),
+      party: party.iter().map(CharacterSave::new).collect(),
+      reserves: inventory.get_reserves().iter().map(CharacterSave::new).collect(),
+      inventory: inventory.get_items().iter().map(|(item, quantity): &(Item, u32)| (item.get_name(), *quantity))
+        .chain(inventory.get_equipment().iter().map(|(equipment, quantity): &(Equipment, u32)| (equipment.get_name(), *quantity)))
+        .collect(),
+      gold: inventory.get_gold()
+    }
+  }
+
+  pub fn from_json(json: &str, content: &Content) -> Result<Self, String> {
+    let save_data: SaveData = serde_json::from_str(json).map_err(|error| format!("Invalid save data: {}", error))?;
+    if save_data.version > SAVE_VERSION {
+      return Err(format!("Save data version {} is newer than the supported version {}", save_data.version, SAVE_VERSION));
+    }
+    let map = match maps::get_map(&save_data.map) {
+      Some(get_map) => get_map(&mut Player::new()),
+      None => return Err(format!("Save data refers to unknown map '{}'", save_data.map))
+    };
+    if !map.is_tile_empty(save_data.player_x, save_data.player_y) {
+      return Err(format!("Save data puts the player on a blocked tile at {}, {}", save_data.player_x, save_data.player_y));
+    }
+    if save_data.party.is_empty() || save_data.party.len() > MAX_PARTY_SIZE {
+      return Err(format!("Save data must have between 1 and {} party members", MAX_PARTY_SIZE));
+    }
+    // battle tells party members from enemies by id, so they have to match the party order
+    for (index, character) in save_data.party.iter().enumerate() {
+      if character.id != index + 1 {
+        return Err(format!("Save data gives party member '{}' id {} instead of {}", character.name, character.id, index + 1));
+      }
+    }
+    if let Some(character) = save_data.reserves.iter().find(|character: &&CharacterSave| character.id != 0) {
+      return Err(format!("Save data gives reserve member '{}' party id {}", character.name, character.id));
+    }
+    for character in save_data.party.iter().chain(save_data.reserves.iter()) {
+      if character.level == 0 {
+        return Err(format!("Save data puts '{}' at level 0", character.name));
+      }
+      character.create_character(content)?;
+    }
+    for (item_name, _) in save_data.inventory.iter() {
+      if !content.is_inventory_entry(item_name) {
+        return Err(format!("Save data refers to unknown item '{}'", item_name));
+      }
+    }
+    Ok(save_data)
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).unwrap()
+  }
+
+  pub fn apply(
+    &self,
+    content: &Content,
+    map: &mut Map,
+    player: &mut Player,
+    party: &mut Vec<Character>,
+    inventory: &mut Inventory
+  ) -> Result<(), String> {
+    let get_map = maps::get_map(&self.map).ok_or_else(|| format!("Save data refers to unknown map '{}'", self.map))?;
+    // build everything before touching the game, so a bad save leaves it as it was
+    let new_party = self.party.iter().map(|saved: &CharacterSave| saved.create_character(content)).collect::<Result<Vec<Character>, String>>()?;
+    let new_reserves = self.reserves.iter().map(|saved: &CharacterSave| saved.create_character(content)).collect::<Result<Vec<Character>, String>>()?;
+    map.set_map(get_map(player));
+    player.set(self.player_x, self.player_y, self.player_direction);
+    *party = new_party;
+    *inventory = Inventory::new();
+    inventory.set_reserves(new_reserves);
+    for (item_name, quantity) in self.inventory.iter() {
+      content.add_to_inventory(inventory, item_name, *quantity);
+    }
+    inventory.add_gold(self.gold);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn starting_save(content: &Content) -> SaveData {
+    let mut player = Player::new();
+    let map = maps::get_map("debug_room").unwrap()(&mut player);
+    SaveData::new(&map, &player, &content.create_starting_party(), &content.create_starting_inventory())
+  }
+
+  // edits the saved json the way a hand edited or corrupted save would
+  fn edited_save(content: &Content, edit: fn(&mut serde_json::Value)) -> String {
+    let mut json: serde_json::Value = serde_json::from_str(&starting_save(content).to_json()).unwrap();
+    edit(&mut json);
+    json.to_string()
+  }
+
+  #[test]
+  fn save_round_trips_through_json() {
+    let content = Content::default_content();
+    let json = starting_save(&content).to_json();
+    let save = SaveData::from_json(&json, &content).unwrap();
+    assert_eq!(save.to_json(), json);
+
+    let mut player = Player::new();
+    let mut map = maps::get_map("").unwrap()(&mut player);
+    let mut party = Vec::new();
+    let mut inventory = Inventory::new();
+    save.apply(&content, &mut map, &mut player, &mut party, &mut inventory).unwrap();
+    assert_eq!(map.get_name(), "debug_room");
+    assert_eq!(SaveData::new(&map, &player, &party, &inventory).to_json(), json);
+  }
+
+  #[test]
+  fn save_without_party_members_is_rejected() {
+    let content = Content::default_content();
+    let json = edited_save(&content, |json: &mut serde_json::Value| json["party"] = serde_json::json!([]));
+    assert!(SaveData::from_json(&json, &content).is_err());
+  }
+
+  #[test]
+  fn save_with_too_many_party_members_is_rejected() {
+    let content = Content::default_content();
+    let json = edited_save(&content, |json: &mut serde_json::Value| {
+      let mut extra = json["reserves"][0].clone();
+      extra["id"] = serde_json::json!(5);
+      json["party"].as_array_mut().unwrap().push(extra);
+    });
+    assert!(SaveData::from_json(&json, &content).is_err());
+  }
+
+  #[test]
+  fn save_with_out_of_order_party_ids_is_rejected() {
+    let content = Content::default_content();
+    let json = edited_save(&content, |json: &mut serde_json::Value| json["party"][1]["id"] = serde_json::json!(5));
+    assert!(SaveData::from_json(&json, &content).is_err());
+    let json = edited_save(&content, |json: &mut serde_json::Value| json["party"][1]["id"] = serde_json::json!(1));
+    assert!(SaveData::from_json(&json, &content).is_err());
+    let json = edited_save(&content, |json: &mut serde_json::Value| json["reserves"][0]["id"] = serde_json::json!(2));
+    assert!(SaveData::from_json(&json, &content).is_err());
+  }
+
+  #[test]
+  fn save_with_unknown_character_is_rejected() {
+    let content = Content::default_content();
+    let json = edited_save(&content, |json: &mut serde_json::Value| json["reserves"][0]["name"] = serde_json::json!("Nobody"));
+    assert!(SaveData::from_json(&json, &content).is_err());
+  }
+
+  #[test]
+  fn save_with_player_off_the_map_or_on_a_wall_is_rejected() {
+    let content = Content::default_content();
+    let json = edited_save(&content, |json: &mut serde_json::Value| json["player_x"] = serde_json::json!(1000));
+    assert!(SaveData::from_json(&json, &content).is_err());
+    let json = edited_save(&content, |json: &mut serde_json::Value| {
+      json["player_x"] = serde_json::json!(0);
+      json["player_y"] = serde_json::json!(0);
+    });
+    assert!(SaveData::from_json(&json, &content).is_err());
+  }
+
+  #[test]
+  fn save_with_level_zero_member_is_rejected() {
+    let content = Content::default_content();
+    let json = edited_save(&content, |json: &mut serde_json::Value| json["party"][0]["level"] = serde_json::json!(0));
+    assert!(SaveData::from_json(&json, &content).is_err());
+  }
+
+  #[test]
+  fn save_with_unknown_equipment_is_rejected() {
+    let content = Content::default_content();
+    let json = edited_save(&content, |json: &mut serde_json::Value| json["party"][0]["equipment"] = serde_json::json!(["Nothing"]));
+    assert!(SaveData::from_json(&json, &content).is_err());
+  }
+
+  #[test]
+  fn applying_a_save_with_unknown_members_leaves_the_game_untouched() {
+    let content = Content::default_content();
+    let json = edited_save(&content, |json: &mut serde_json::Value| json["reserves"][0]["name"] = serde_json::json!("Nobody"));
+    let save: SaveData = serde_json::from_str(&json).unwrap();
+
+    let mut player = Player::new();
+    let mut map = maps::get_map("").unwrap()(&mut player);
+    let mut party = content.create_starting_party();
+    let mut inventory = Inventory::new();
+    assert!(save.apply(&content, &mut map, &mut player, &mut party, &mut inventory).is_err());
+    assert_eq!(party.len(), content.create_starting_party().len());
+    assert!(inventory.get_reserves().is_empty());
+    assert_ne!(map.get_name(), "debug_room");
+  }
+}
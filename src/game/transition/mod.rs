@@ -1,10 +1,13 @@
-use crate::game::battle::Battle;
+use crate::game::backend::Renderer;
+use crate::game::battle::{Battle, BattleSpoils};
 use crate::game::battle::character::Character;
-use crate::game::data::menus;
+use crate::game::data::{maps, menus};
+use crate::game::inventory::Inventory;
 use crate::game::map::Map;
 use crate::game::map::player::Player;
 use crate::game::menu::MenuScreen;
-use crate::webgl::shader_program::ShaderProgram;
+use crate::game::save::SaveData;
+use crate::game::GameData;
 
 pub enum TransitionStyle {
   None,
@@ -12,9 +15,11 @@ pub enum TransitionStyle {
   BlackIn,
   BattleIn,
   BattleOut,
-  BattleEndScreen(for<'a> fn(&'a mut Vec<Character>, u32) -> MenuScreen, u32),
-  MenuIn(for<'a> fn(&'a mut Vec<Character>) -> MenuScreen),
+  BattleEndScreen(for<'a, 'b, 'c> fn(&'a mut [Character], &'b Inventory, &'c BattleSpoils) -> MenuScreen, BattleSpoils),
+  MenuIn(for<'a, 'b> fn(&'a mut [Character], &'b Inventory) -> MenuScreen),
   ChangeScene(for<'a> fn(&'a mut Player) -> Map),
+  LoadGame,
+  ToTitle,
   WhiteOut,
   BlackOut
 }
@@ -36,7 +41,16 @@ impl Transition {
     self.style = transition;
   }
 
-  pub fn update(&mut self, map: &mut Map, player: &mut Player, party: &mut Vec<Character>, battle: &mut Battle, menu: &mut MenuScreen) {
+  pub fn update(
+    &mut self,
+    map: &mut Map,
+    player: &mut Player,
+    battle: &mut Battle,
+    menu: &mut MenuScreen,
+    game_data: &mut GameData,
+    save: &Option<SaveData>
+  ) {
+    let GameData { party, inventory, content, .. } = game_data;
     match self.style {
       TransitionStyle::None => (),
       TransitionStyle::WhiteIn | TransitionStyle::BlackIn => {
@@ -59,33 +73,66 @@ impl Transition {
       TransitionStyle::BattleOut => {
         self.opacity = ((self.opacity + 0.3) * 0.9).min(1.);
         if self.opacity == 1. {
-          menu.set_menu(menus::none_menu(party));
+          for character in party.iter_mut() {
+            character.get_battle_state_mut().clear_battle_statuses();
+          }
+          menu.set_menu(menus::none_menu(party, inventory));
           battle.set_fighting_state(false);
           self.set(TransitionStyle::BlackOut);
         }
       },
-      TransitionStyle::BattleEndScreen(get_new_menu_function, experience) => {
+      TransitionStyle::BattleEndScreen(get_new_menu_function, ref spoils) => {
         self.opacity = ((self.opacity + 0.3) * 0.9).min(1.);
         if self.opacity == 1. {
-          menu.set_menu(get_new_menu_function(party, experience));
+          // the victory screen starts from the wallet before the battle and only counts the spoils up,
+          // they are banked right away so leaving the screen early can't lose them
+          menu.set_menu(get_new_menu_function(party, inventory, spoils));
+          inventory.add_gold(spoils.gold);
+          for item_name in spoils.items.iter() {
+            content.add_to_inventory(inventory, item_name, 1);
+          }
           self.set(TransitionStyle::BlackOut);
         }
       },
       TransitionStyle::MenuIn(get_new_menu_function) => {
         self.opacity = ((self.opacity + 0.3) * 0.9).min(1.);
         if self.opacity == 1. {
-          menu.set_menu(get_new_menu_function(party));
+          menu.set_menu(get_new_menu_function(party, inventory));
           self.set(TransitionStyle::BlackOut);
         }
       },
       TransitionStyle::ChangeScene(get_new_map_function) => {
         self.opacity = ((self.opacity + 0.3) * 0.9).min(1.);
         if self.opacity == 1. {
-          menu.set_menu(menus::none_menu(party));
+          menu.set_menu(menus::none_menu(party, inventory));
           map.set_map(get_new_map_function(player));
           self.set(TransitionStyle::BlackOut);
         }
       },
+      TransitionStyle::LoadGame => {
+        self.opacity = ((self.opacity + 0.3) * 0.9).min(1.);
+        if self.opacity == 1. {
+          match save.as_ref().map(|save_data: &SaveData| save_data.apply(content, map, player, party, inventory)) {
+            Some(Ok(())) => {
+              menu.set_menu(menus::none_menu(party, inventory));
+              battle.set_fighting_state(false);
+              self.set(TransitionStyle::BlackOut);
+            },
+            _ => self.set(TransitionStyle::ToTitle)
+          }
+        }
+      },
+      TransitionStyle::ToTitle => {
+        self.opacity = ((self.opacity + 0.3) * 0.9).min(1.);
+        if self.opacity == 1. {
+          *party = content.create_starting_party();
+          *inventory = content.create_starting_inventory();
+          map.set_map(maps::none_map(player));
+          menu.set_menu(menus::title_menu(party, inventory));
+          battle.set_fighting_state(false);
+          self.set(TransitionStyle::BlackOut);
+        }
+      },
       TransitionStyle::WhiteOut | TransitionStyle::BlackOut => {
         self.opacity = ((self.opacity - 0.25) * 1.1).max(0.);
         if self.opacity == 0. {
@@ -102,7 +149,7 @@ impl Transition {
     }
   }
 
-  pub fn draw(&self, program: &mut ShaderProgram) {
+  pub fn draw(&self, program: &mut dyn Renderer) {
     let sprite_key = match self.style {
       TransitionStyle::WhiteIn | TransitionStyle::WhiteOut => String::from("white"),
       _ => String::from("black")
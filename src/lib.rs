@@ -1,10 +1,13 @@
 mod webgl;
-mod game;
+pub mod game;
 
 use webgl::audio::Audio;
 use webgl::{gl_setup, keyboard};
+use webgl::keyboard::KeyboardInput;
+use webgl::random::JsRandom;
 use webgl::shader_program::ShaderProgram;
 use crate::game::GameState;
+use crate::game::backend::Rng;
 
 #[macro_use]
 extern crate lazy_static;
@@ -22,6 +25,7 @@ extern "C" {
 #[wasm_bindgen]
 pub struct GameClient {
   game_state: GameState,
+  input: KeyboardInput,
   audio: Audio,
   shader_program: ShaderProgram,
   gl: WebGlRenderingContext
@@ -36,7 +40,8 @@ impl GameClient {
     let gl = gl_setup::initialize_webgl_context().unwrap();
     keyboard::attach_keyboard_events().unwrap();
     Self {
-      game_state: GameState::new(),
+      game_state: GameState::new((JsRandom.random() * u32::MAX as f64) as u32),
+      input: KeyboardInput,
       audio: Audio::new(),
       shader_program: ShaderProgram::new(&gl),
       gl
@@ -44,7 +49,31 @@ impl GameClient {
   }
 
   pub fn update(&mut self) {
-    self.game_state.update(&mut self.audio);
+    self.game_state.update(&mut self.input, &mut self.audio);
+  }
+
+  pub fn get_seed(&self) -> u32 {
+    self.game_state.get_seed()
+  }
+
+  pub fn set_seed(&mut self, seed: u32) {
+    self.game_state.set_seed(seed);
+  }
+
+  pub fn save_game(&mut self) -> Option<String> {
+    self.game_state.save_game()
+  }
+
+  pub fn load_game(&mut self, save_data: String) -> Result<(), JsValue> {
+    self.game_state.load_game(&save_data).map_err(|error: String| JsValue::from_str(&error))
+  }
+
+  pub fn set_party_level(&mut self, level: u32) {
+    self.game_state.set_party_level(level);
+  }
+
+  pub fn load_content(&mut self, content: String) -> Result<(), JsValue> {
+    self.game_state.load_content(&content).map_err(|error: String| JsValue::from_str(&error))
   }
 
   pub fn add_sprite(&mut self, key: String, sprite: HtmlImageElement) {
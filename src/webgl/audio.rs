@@ -1,4 +1,5 @@
-use web_sys::{window, HtmlAudioElement};
+use web_sys::HtmlAudioElement;
+use crate::game::backend::AudioPlayer;
 
 pub struct Audio {
   soundtracks: Vec<HtmlAudioElement>,
@@ -19,7 +20,7 @@ impl Audio {
     }
   }
 
-  pub fn update(&mut self, file_name: &String) {
+  pub fn update(&mut self, file_name: &str) {
     for (index, element) in self.soundtracks.iter().enumerate() {
       if element.src().contains(file_name) {
         self.soundtracks[index].set_volume(self.soundtrack_volume);
@@ -77,3 +78,13 @@ impl Audio {
     self.sfx_volume = value;
   }
 }
+
+impl AudioPlayer for Audio {
+  fn update(&mut self, file_name: &str) {
+    Audio::update(self, file_name);
+  }
+
+  fn play_sfx(&mut self, file_name: &str) {
+    Audio::play_sfx(self, file_name);
+  }
+}
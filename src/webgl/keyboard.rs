@@ -2,6 +2,7 @@ use std::sync::{Arc,Mutex};
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen::prelude::*;
 use web_sys::*;
+use crate::game::backend::Input;
 
 lazy_static! {
   static ref KEY_STATES: Mutex<Arc<Keyboard>> = Mutex::new(Arc::new(Keyboard::new()));
@@ -71,6 +72,18 @@ pub fn is_down(key: &str) -> bool {
   false
 }
 
+pub struct KeyboardInput;
+
+impl Input for KeyboardInput {
+  fn is_pressed(&mut self, key: &str) -> bool {
+    is_pressed(key)
+  }
+
+  fn is_down(&self, key: &str) -> bool {
+    is_down(key)
+  }
+}
+
 pub struct Keyboard {
   a: bool,
   a_held: bool,
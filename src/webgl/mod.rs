@@ -1,6 +1,7 @@
 pub mod audio;
 pub mod gl_setup;
 pub mod keyboard;
+pub mod random;
 pub mod shader_program;
 pub mod shaders;
 
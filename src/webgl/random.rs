@@ -0,0 +1,10 @@
+use js_sys::Math::random;
+use crate::game::backend::Rng;
+
+pub struct JsRandom;
+
+impl Rng for JsRandom {
+  fn random(&mut self) -> f64 {
+    random()
+  }
+}
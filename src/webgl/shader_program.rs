@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use wasm_bindgen::JsCast;
 use web_sys::*;
 use web_sys::WebGlRenderingContext as GL;
+use crate::game::backend::Renderer;
 use crate::webgl::*;
 
 const SPRITE_MAX_SIZE: usize = 1000;
@@ -124,3 +125,9 @@ impl ShaderProgram {
     self.data_size = 0;
   }
 }
+
+impl Renderer for ShaderProgram {
+  fn draw(&mut self, key: String, x: f32, y: f32, width: f32, height: f32, opacity: f32) {
+    ShaderProgram::draw(self, key, x, y, width, height, opacity);
+  }
+}
@@ -0,0 +1,79 @@
+use jrpg_wasm_engine::game::GameState;
+use jrpg_wasm_engine::game::backend::headless::{DrawCall, RecordingAudio, RecordingRenderer, ScriptedInput};
+
+// Runs every scripted frame through the game and returns what was drawn on each of them.
+fn run(game: &mut GameState, input: &mut ScriptedInput, audio: &mut RecordingAudio, renderer: &mut RecordingRenderer) -> Vec<Vec<DrawCall>> {
+  let mut frames = Vec::new();
+  while !input.is_finished() {
+    input.advance_frame();
+    game.update(input, audio);
+    game.draw(renderer);
+    frames.push(renderer.take_frame());
+  }
+  frames
+}
+
+fn press(input: &mut ScriptedInput, key: &str, wait: usize) {
+  input.push_frame(vec![key]);
+  for _ in 0..wait {
+    input.push_frame(vec![]);
+  }
+}
+
+fn hold(input: &mut ScriptedInput, key: &str, frames: usize) {
+  for _ in 0..frames {
+    input.push_frame(vec![key]);
+  }
+}
+
+// the title cursor starts on "Debug room", New Game has nothing behind it yet
+#[test]
+fn debug_room_entry_starts_on_the_map() {
+  let mut game = GameState::new(3);
+  let mut input = ScriptedInput::new(vec![]);
+  let mut audio = RecordingAudio::new();
+  let mut renderer = RecordingRenderer::new();
+  assert!(game.save_game().is_none());
+
+  press(&mut input, "a", 60);
+  let frames = run(&mut game, &mut input, &mut audio, &mut renderer);
+  assert!(audio.get_soundtrack().is_some());
+  assert!(frames.last().unwrap().iter().any(|call: &DrawCall| call.key.starts_with("characters/")));
+  let save = game.save_game().unwrap();
+  assert!(save.contains("\"map\":\"debug_room\""));
+}
+
+#[test]
+fn opening_the_main_menu_draws_it_over_the_map() {
+  let mut game = GameState::new(3);
+  let mut input = ScriptedInput::new(vec![]);
+  let mut audio = RecordingAudio::new();
+  let mut renderer = RecordingRenderer::new();
+
+  press(&mut input, "a", 60);
+  press(&mut input, "f", 60);
+  let frames = run(&mut game, &mut input, &mut audio, &mut renderer);
+  let menu_frame = frames.last().unwrap();
+  assert!(menu_frame.iter().any(|call: &DrawCall| call.key == "cursor"));
+  assert!(!menu_frame.iter().any(|call: &DrawCall| call.key.starts_with("characters/")));
+}
+
+#[test]
+fn same_seed_and_input_replay_the_same_game() {
+  let mut replays = Vec::new();
+  for _ in 0..2 {
+    let mut game = GameState::new(3);
+    let mut input = ScriptedInput::new(vec![]);
+    let mut audio = RecordingAudio::new();
+    let mut renderer = RecordingRenderer::new();
+    press(&mut input, "a", 60);
+    for _ in 0..4 {
+      hold(&mut input, "down", 90);
+      hold(&mut input, "up", 90);
+      press(&mut input, "a", 30);
+    }
+    let frames = run(&mut game, &mut input, &mut audio, &mut renderer);
+    replays.push((frames, audio.get_played_sfxs().clone(), game.save_game()));
+  }
+  assert!(replays[0] == replays[1]);
+}